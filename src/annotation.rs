@@ -0,0 +1,117 @@
+use crate::fen::get_square_name;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnnotationColor {
+  Green,
+  Red,
+  Yellow,
+  Blue,
+}
+
+impl AnnotationColor {
+  // the letter used by the `%cal` and `%csl` comment commands
+  pub fn get_letter(self) -> char {
+    match self {
+      AnnotationColor::Green => 'G',
+      AnnotationColor::Red => 'R',
+      AnnotationColor::Yellow => 'Y',
+      AnnotationColor::Blue => 'B',
+    }
+  }
+}
+
+// the from square, the to square and the colour
+pub type Arrow = ((usize, usize), (usize, usize), AnnotationColor);
+
+// arrows and highlighted squares drawn over the position
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Annotations {
+  pub arrows: Vec<Arrow>,
+  pub highlights: Vec<((usize, usize), AnnotationColor)>,
+}
+
+impl Annotations {
+  pub fn new() -> Annotations {
+    Default::default()
+  }
+
+  // drawing the same arrow again removes it, in another colour it
+  // changes colour
+  pub fn toggle_arrow(&mut self, from: (usize, usize), to: (usize, usize), color: AnnotationColor) {
+    match self
+      .arrows
+      .iter()
+      .position(|arrow| arrow.0 == from && arrow.1 == to)
+    {
+      Some(idx) if self.arrows[idx].2 == color => {
+        self.arrows.remove(idx);
+      }
+      Some(idx) => self.arrows[idx].2 = color,
+      None => self.arrows.push((from, to, color)),
+    }
+  }
+
+  pub fn toggle_highlight(&mut self, square: (usize, usize), color: AnnotationColor) {
+    match self
+      .highlights
+      .iter()
+      .position(|highlight| highlight.0 == square)
+    {
+      Some(idx) if self.highlights[idx].1 == color => {
+        self.highlights.remove(idx);
+      }
+      Some(idx) => self.highlights[idx].1 = color,
+      None => self.highlights.push((square, color)),
+    }
+  }
+
+  pub fn get_highlight(&self, square: (usize, usize)) -> Option<AnnotationColor> {
+    self
+      .highlights
+      .iter()
+      .find(|highlight| highlight.0 == square)
+      .map(|highlight| highlight.1)
+  }
+
+  pub fn clear(&mut self) {
+    self.arrows.clear();
+    self.highlights.clear();
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.arrows.is_empty() && self.highlights.is_empty()
+  }
+
+  // the commands as a PGN comment, like `{[%csl Gd4][%cal Re2e4]}`
+  pub fn to_pgn_comment(&self) -> Option<String> {
+    if self.is_empty() {
+      return None;
+    }
+    let mut comment = String::from("{");
+    if !self.highlights.is_empty() {
+      let squares: Vec<String> = self
+        .highlights
+        .iter()
+        .map(|(square, color)| format!("{}{}", color.get_letter(), get_square_name(*square)))
+        .collect();
+      comment += format!("[%csl {}]", squares.join(",")).as_str();
+    }
+    if !self.arrows.is_empty() {
+      let arrows: Vec<String> = self
+        .arrows
+        .iter()
+        .map(|(from, to, color)| {
+          format!(
+            "{}{}{}",
+            color.get_letter(),
+            get_square_name(*from),
+            get_square_name(*to)
+          )
+        })
+        .collect();
+      comment += format!("[%cal {}]", arrows.join(",")).as_str();
+    }
+    comment.push('}');
+    Some(comment)
+  }
+}
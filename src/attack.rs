@@ -0,0 +1,105 @@
+use crate::position::{Piece, PlayerPiece, COLUMNS, ROWS};
+
+pub const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+  (-2, -1),
+  (-2, 1),
+  (-1, -2),
+  (-1, 2),
+  (1, -2),
+  (1, 2),
+  (2, -1),
+  (2, 1),
+];
+pub const KING_OFFSETS: [(i32, i32); 8] = [
+  (-1, -1),
+  (-1, 0),
+  (-1, 1),
+  (0, -1),
+  (0, 1),
+  (1, -1),
+  (1, 0),
+  (1, 1),
+];
+pub const ROOK_DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+pub fn offset_square(square: (usize, usize), offset: (i32, i32)) -> Option<(usize, usize)> {
+  let i = square.0 as i32 + offset.0;
+  let j = square.1 as i32 + offset.1;
+  if (0..COLUMNS as i32).contains(&i) && (0..ROWS as i32).contains(&j) {
+    Some((i as usize, j as usize))
+  } else {
+    None
+  }
+}
+
+pub fn find_king(board: &[[PlayerPiece; ROWS]; COLUMNS], player: usize) -> Option<(usize, usize)> {
+  (0..COLUMNS)
+    .flat_map(|i| (0..ROWS).map(move |j| (i, j)))
+    .find(|&(i, j)| board[i][j].piece_idx == Piece::King && board[i][j].player == player)
+}
+
+// the squares of the pieces of `player` that attack `square`
+pub fn get_attackers(
+  board: &[[PlayerPiece; ROWS]; COLUMNS],
+  square: (usize, usize),
+  player: usize,
+) -> Vec<(usize, usize)> {
+  let mut attackers = Vec::new();
+  let is_piece = |from: (usize, usize), pieces: &[Piece]| -> bool {
+    let piece = board[from.0][from.1];
+    piece.player == player && pieces.contains(&piece.piece_idx)
+  };
+
+  // white pawns capture towards the eighth rank, which is row 0
+  let pawn_row = if player == 1 { 1 } else { -1 };
+  for offset in [(pawn_row, -1), (pawn_row, 1)] {
+    if let Some(from) = offset_square(square, offset) {
+      if is_piece(from, &[Piece::Pawn]) {
+        attackers.push(from);
+      }
+    }
+  }
+  for (offsets, piece) in [(KNIGHT_OFFSETS, Piece::Knight), (KING_OFFSETS, Piece::King)] {
+    for offset in offsets {
+      if let Some(from) = offset_square(square, offset) {
+        if is_piece(from, &[piece]) {
+          attackers.push(from);
+        }
+      }
+    }
+  }
+  for (directions, piece) in [
+    (ROOK_DIRECTIONS, Piece::Rook),
+    (BISHOP_DIRECTIONS, Piece::Bishop),
+  ] {
+    for direction in directions {
+      let mut curr = offset_square(square, direction);
+      while let Some(from) = curr {
+        if board[from.0][from.1].piece_idx != Piece::None {
+          if is_piece(from, &[piece, Piece::Queen]) {
+            attackers.push(from);
+          }
+          break;
+        }
+        curr = offset_square(from, direction);
+      }
+    }
+  }
+  attackers
+}
+
+pub fn is_square_attacked(
+  board: &[[PlayerPiece; ROWS]; COLUMNS],
+  square: (usize, usize),
+  player: usize,
+) -> bool {
+  !get_attackers(board, square, player).is_empty()
+}
+
+pub fn is_in_check(board: &[[PlayerPiece; ROWS]; COLUMNS], player: usize) -> bool {
+  match find_king(board, player) {
+    Some(square) => is_square_attacked(board, square, 1 - player),
+    None => false,
+  }
+}
@@ -1,74 +1,99 @@
-use crate::{fen::generate_fen_from_board, Rect};
+use crate::{
+  annotation::{AnnotationColor, Annotations},
+  attack::{find_king, is_in_check},
+  eval::evaluate,
+  fen::{get_piece_for_character, CastlingNotation, FenError},
+  movegen::{get_castling_rook, Move},
+  pgn::{generate_pgn, PgnTags},
+  position::{Piece, PlayerPiece, Position, COLUMNS, ROWS},
+  transform::{mirror_files, shift_pieces, swap_colours},
+  Rect,
+};
 use allegro::{
   Bitmap, BitmapDrawingFlags, BitmapLike, Color, Core, Display,
-  Event::{self, MouseButtonDown, MouseButtonUp},
-  Flag,
+  Event::{self, KeyDown, MouseButtonDown, MouseButtonUp},
+  Flag, KeyCode,
 };
+use allegro_font::{Font, FontAlign, FontDrawing};
 use allegro_primitives::PrimitivesAddon;
+use allegro_sys::{al_get_keyboard_state, al_key_down, ALLEGRO_KEYBOARD_STATE};
 use clipboard::{ClipboardContext, ClipboardProvider};
+use std::{error::Error, fs, ptr};
 
-pub const ROWS: usize = 8;
-pub const COLUMNS: usize = 8;
 const BOX_DIMENSION: f32 = 75.0;
 const PADDING: f32 = 5.0;
 const IMG_WIDTH: f32 = 45.0;
+const BAR_WIDTH: f32 = 20.0;
+const BAR_MARGIN: f32 = 15.0;
+const ARROW_WIDTH: f32 = 10.0;
+const ARROW_HEAD: f32 = 22.0;
 
-#[derive(Default, Debug, Copy, Clone)]
-pub enum Piece {
-  Bishop,
-  King,
-  Knight,
-  Pawn,
-  Queen,
-  Rook,
-  #[default]
-  None,
-}
+// in the order the picker shows them
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
 
-impl Piece {
-  fn from_usize(value: usize) -> Piece {
-    match value {
-      0 => Piece::Bishop,
-      1 => Piece::King,
-      2 => Piece::Knight,
-      3 => Piece::Pawn,
-      4 => Piece::Queen,
-      5 => Piece::Rook,
-      _ => Piece::None,
-    }
-  }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Source {
+  Shelf,
+  Board { i: usize, j: usize },
 }
 
-#[derive(Debug, Default, Copy, Clone)]
-pub enum Source {
-  #[default]
-  Shelf,
-  Board {
-    i: usize,
-    j: usize,
-  },
+// a piece picked up with the mouse and where it was taken from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct SelectedPiece {
+  piece: PlayerPiece,
+  source: Source,
 }
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct PlayerPiece {
-  pub player: usize,
-  pub piece_idx: Piece,
-  pub source: Source,
+// a pawn dropped on the last row, waiting for the piece it becomes
+struct Promotion {
+  selected: SelectedPiece,
+  square: (usize, usize),
 }
+
 pub struct Board {
   rect: Rect,
-  board: [[PlayerPiece; ROWS]; COLUMNS],
-  selected_piece: Option<PlayerPiece>,
+  position: Position,
+  selected_piece: Option<SelectedPiece>,
   // the player at the bottom
   // i couldn't find a better variable name
   player_pov: usize,
+  // pieces only make legal moves in analysis mode
+  analysis_mode: bool,
+  promotion: Option<Promotion>,
+  // the position before the last move played, its annotations and the
+  // move
+  played_move: Option<(Position, Annotations, Move)>,
+  // the from and to squares of the moves drawn as numbered arrows
+  move_line: Vec<((usize, usize), (usize, usize))>,
+  annotations: Annotations,
+  // the square a right button drag started on
+  annotation_start: Option<(usize, usize)>,
+  // how copied FENs write castling rights
+  castling_notation: CastlingNotation,
+  // keys place pieces after a click on the board, until a click
+  // elsewhere
+  has_keyboard_focus: bool,
+  // the square keys act on
+  cursor: (usize, usize),
+  // the piece clicks put on the board, picked on the shelf, none while
+  // clicks move pieces
+  brush: Option<PlayerPiece>,
+  // the piece keys put on the board, picked by typing its FEN letter
+  typed_piece: Option<PlayerPiece>,
+  // the shelf side and cell a press started on, releasing on the same
+  // cell arms the brush
+  shelf_press: Option<(usize, usize)>,
+  // a file letter waiting for the rank of a typed square
+  typed_file: Option<char>,
+  // the left and right arrows step through the loaded game instead of
+  // moving the cursor
+  is_game_loaded: bool,
 }
 
 impl Board {
   pub fn new() -> Board {
-    let board = Default::default();
     Board {
-      board,
+      position: Position::new(),
       rect: Rect::new(
         100.0,
         100.0,
@@ -76,10 +101,25 @@ impl Board {
         BOX_DIMENSION * (COLUMNS) as f32,
       ),
       selected_piece: None,
-      player_pov: 0,
+      player_pov: 1,
+      analysis_mode: false,
+      promotion: None,
+      played_move: None,
+      move_line: Vec::new(),
+      annotations: Annotations::new(),
+      annotation_start: None,
+      castling_notation: CastlingNotation::XFen,
+      has_keyboard_focus: false,
+      cursor: (4, 4),
+      brush: None,
+      typed_piece: None,
+      shelf_press: None,
+      typed_file: None,
+      is_game_loaded: false,
     }
   }
 
+  #[allow(clippy::too_many_arguments)]
   pub fn draw(
     &self,
     core: &Core,
@@ -88,11 +128,23 @@ impl Board {
     white: &Bitmap,
     black: &Bitmap,
     pointer: &Bitmap,
+    font: &Font,
   ) {
+    let (mouse_x, mouse_y) = Board::get_mouse_position(display);
+    self.draw_evaluation_bar(
+      primitives,
+      evaluate(&self.get_preview_board(mouse_x as f32, mouse_y as f32)),
+    );
     {
       let (mut x, mut y) = (self.rect.x, self.rect.y);
       let dim = BOX_DIMENSION - (4.0 * PADDING);
       for i in 0..2 {
+        // the shelf at the top holds the pieces of the player at the top
+        let side = if i == 0 {
+          1 - self.player_pov
+        } else {
+          self.player_pov
+        };
         primitives.draw_filled_rectangle(
           x,
           y,
@@ -102,13 +154,16 @@ impl Board {
         );
         for j in 0..COLUMNS - 1 {
           if j == 0 {
-            primitives.draw_filled_rectangle(
-              x,
-              y,
-              x + BOX_DIMENSION,
-              y + BOX_DIMENSION,
-              Color::from_rgb(101, 135, 64),
-            );
+            // the pointer is the tool while there is no brush
+            if self.brush.is_none() {
+              primitives.draw_filled_rectangle(
+                x,
+                y,
+                x + BOX_DIMENSION,
+                y + BOX_DIMENSION,
+                Color::from_rgb(101, 135, 64),
+              );
+            }
             core.draw_scaled_bitmap(
               pointer,
               0.0,
@@ -122,9 +177,13 @@ impl Board {
               BitmapDrawingFlags::zero(),
             );
           } else {
-            match &self.selected_piece {
+            match &self
+              .selected_piece
+              .map(|selected| selected.piece)
+              .or(self.brush)
+            {
               Some(value) => {
-                if (i == value.player) && (j == (value.piece_idx as usize) + 1) {
+                if (side == value.player) && (j == (value.piece_idx as usize) + 1) {
                   primitives.draw_filled_rectangle(
                     x,
                     y,
@@ -137,7 +196,7 @@ impl Board {
               None => {}
             }
             core.draw_scaled_bitmap(
-              if i == self.player_pov { black } else { white },
+              Board::get_sprite(side, white, black),
               IMG_WIDTH * ((j - 1) as f32),
               0.0,
               IMG_WIDTH,
@@ -155,14 +214,18 @@ impl Board {
         y = self.rect.y + self.rect.height + BOX_DIMENSION;
       }
     }
+    let player = self.position.player;
+    let checked_king =
+      find_king(&self.position.board, player).filter(|_| is_in_check(&self.position.board, player));
     let (mut curr_x, mut curr_y, mut switch) = (self.rect.x, self.rect.y + BOX_DIMENSION, true);
     for k in 0..COLUMNS {
       for n in 0..ROWS {
-        let piece = self.board[k][n];
+        let (row, column) = self.orient(k, n);
+        let piece = self.position.board[row][column];
         if self.selected_piece.is_some() {
           match self.selected_piece.unwrap().source {
             Source::Board { i, j } => {
-              if (i == k) && (j == n) {
+              if (i == row) && (j == column) {
                 primitives.draw_filled_rectangle(
                   curr_x,
                   curr_y,
@@ -181,16 +244,30 @@ impl Board {
         } else {
           self.draw_boxes(primitives, curr_x, curr_y, n, switch);
         }
+        if let Some(color) = self.annotations.get_highlight((row, column)) {
+          primitives.draw_filled_rectangle(
+            curr_x,
+            curr_y,
+            curr_x + BOX_DIMENSION,
+            curr_y + BOX_DIMENSION,
+            Board::get_annotation_color(color, 0.6),
+          );
+        }
+        if checked_king == Some((row, column)) {
+          let half = BOX_DIMENSION / 2.0;
+          primitives.draw_filled_circle(
+            curr_x + half,
+            curr_y + half,
+            half - PADDING,
+            Color::from_rgb(214, 72, 72),
+          );
+        }
         match piece.piece_idx {
           Piece::None => {}
           _ => {
             let dimension = BOX_DIMENSION - (PADDING * 2.0);
             core.draw_scaled_bitmap(
-              if piece.player == self.player_pov {
-                black
-              } else {
-                white
-              },
+              Board::get_sprite(piece.player, white, black),
               IMG_WIDTH * ((piece.piece_idx as i32) as f32),
               0.0,
               IMG_WIDTH,
@@ -210,24 +287,76 @@ impl Board {
       curr_y += BOX_DIMENSION;
     }
 
-    match &self.selected_piece {
-      Some(value) => {
-        let (mut x, mut y) = (0, 0);
-        let window_pos = display.get_window_position();
-        unsafe {
-          allegro_sys::mouse::al_get_mouse_cursor_position(&mut x, &mut y);
-        }
+    if self.has_keyboard_focus {
+      let (k, n) = self.orient(self.cursor.0, self.cursor.1);
+      let (x, y) = (
+        self.rect.x + n as f32 * BOX_DIMENSION,
+        self.rect.y + (k + 1) as f32 * BOX_DIMENSION,
+      );
+      primitives.draw_rectangle(
+        x + 2.0,
+        y + 2.0,
+        x + BOX_DIMENSION - 2.0,
+        y + BOX_DIMENSION - 2.0,
+        Color::from_rgb(54, 146, 231),
+        4.0,
+      );
+    }
+
+    self.draw_move_line(core, primitives, font);
+    for (from, to, color) in self.annotations.arrows.iter() {
+      self.draw_arrow(
+        primitives,
+        *from,
+        *to,
+        Board::get_annotation_color(*color, 0.8),
+      );
+    }
+    if let Some(from) = self.annotation_start {
+      match self.get_square_at(mouse_x as f32, mouse_y as f32) {
+        Some(to) if to != from => self.draw_arrow(
+          primitives,
+          from,
+          to,
+          Board::get_annotation_color(Board::get_modifier_color(), 0.5),
+        ),
+        _ => {}
+      }
+    }
+
+    if let Some(promotion) = &self.promotion {
+      for (rect, piece) in self.get_promotion_rects(promotion.square) {
+        primitives.draw_filled_rectangle(
+          rect.x,
+          rect.y,
+          rect.x + rect.width,
+          rect.y + rect.height,
+          Color::from_rgb(231, 231, 231),
+        );
+        let dimension = BOX_DIMENSION - (PADDING * 2.0);
+        core.draw_scaled_bitmap(
+          Board::get_sprite(promotion.selected.piece.player, white, black),
+          IMG_WIDTH * ((piece as i32) as f32),
+          0.0,
+          IMG_WIDTH,
+          IMG_WIDTH,
+          rect.x + PADDING,
+          rect.y + PADDING,
+          dimension,
+          dimension,
+          BitmapDrawingFlags::zero(),
+        );
+      }
+    }
 
+    match self.selected_piece.map(|selected| selected.piece) {
+      Some(value) => {
         let img_w_half = IMG_WIDTH as i32 / 2;
-        x -= window_pos.0 + img_w_half;
-        y -= window_pos.1 + img_w_half;
+        let x = mouse_x - img_w_half;
+        let y = mouse_y - img_w_half;
 
         core.draw_scaled_bitmap(
-          if value.player == self.player_pov {
-            black
-          } else {
-            white
-          },
+          Board::get_sprite(value.player, white, black),
           IMG_WIDTH * ((value.piece_idx as i32) as f32),
           0.0,
           IMG_WIDTH,
@@ -243,6 +372,212 @@ impl Board {
     }
   }
 
+  pub fn get_square_center(&self, square: (usize, usize)) -> (f32, f32) {
+    let (k, n) = self.orient(square.0, square.1);
+    (
+      self.rect.x + (n as f32 + 0.5) * BOX_DIMENSION,
+      self.rect.y + (k as f32 + 1.5) * BOX_DIMENSION,
+    )
+  }
+
+  // the middle of a cell on the shelf of the side, cell 0 is the pointer
+  // and the pieces follow in `Piece` order
+  pub fn get_shelf_center(&self, side: usize, cell: usize) -> (f32, f32) {
+    let y = if side == self.player_pov {
+      self.rect.y + self.rect.height + BOX_DIMENSION
+    } else {
+      self.rect.y
+    };
+    (
+      self.rect.x + (cell as f32 + 0.5) * BOX_DIMENSION,
+      y + 0.5 * BOX_DIMENSION,
+    )
+  }
+
+  // an arrow from the middle of one square to the middle of another,
+  // the line stops where the head starts so the colours do not add up
+  fn draw_arrow(
+    &self,
+    primitives: &PrimitivesAddon,
+    from: (usize, usize),
+    to: (usize, usize),
+    color: Color,
+  ) {
+    let (x1, y1) = self.get_square_center(from);
+    let (x2, y2) = self.get_square_center(to);
+    let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    if length == 0.0 {
+      return;
+    }
+    let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+    let (base_x, base_y) = (x2 - dx * ARROW_HEAD, y2 - dy * ARROW_HEAD);
+    primitives.draw_line(x1, y1, base_x, base_y, color, ARROW_WIDTH);
+    let half = ARROW_HEAD * 0.6;
+    primitives.draw_filled_triangle(
+      x2,
+      y2,
+      base_x - dy * half,
+      base_y + dx * half,
+      base_x + dy * half,
+      base_y - dx * half,
+      color,
+    );
+  }
+
+  // later moves fade out, each arrow is numbered half way along
+  fn draw_move_line(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    let count = self.move_line.len();
+    for (idx, (from, to)) in self.move_line.iter().enumerate() {
+      let alpha = 0.9 - 0.55 * idx as f32 / count.max(2) as f32;
+      let color = Color::from_rgba_f(0.95 * alpha, 0.6 * alpha, 0.1 * alpha, alpha);
+      self.draw_arrow(primitives, *from, *to, color);
+    }
+    for (idx, (from, to)) in self.move_line.iter().enumerate() {
+      let (x1, y1) = self.get_square_center(*from);
+      let (x2, y2) = self.get_square_center(*to);
+      let (x, y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+      let radius = font.get_line_height() as f32 * 0.6;
+      primitives.draw_filled_circle(x, y, radius, Color::from_rgb(38, 36, 33));
+      core.draw_text(
+        font,
+        Color::from_rgb(255, 255, 255),
+        x,
+        y - font.get_line_height() as f32 / 2.0,
+        FontAlign::Centre,
+        (idx + 1).to_string().as_str(),
+      );
+    }
+  }
+
+  // premultiplied like allegro expects
+  fn get_annotation_color(color: AnnotationColor, alpha: f32) -> Color {
+    let (r, g, b) = match color {
+      AnnotationColor::Green => (0.08, 0.47, 0.11),
+      AnnotationColor::Red => (0.53, 0.13, 0.13),
+      AnnotationColor::Yellow => (0.9, 0.56, 0.0),
+      AnnotationColor::Blue => (0.0, 0.19, 0.53),
+    };
+    Color::from_rgba_f(r * alpha, g * alpha, b * alpha, alpha)
+  }
+
+  // shift, ctrl and alt pick the annotation colour, they are read from
+  // the keyboard state so a key let go outside the window does not stick
+  fn get_modifier_color() -> AnnotationColor {
+    let mut state = ALLEGRO_KEYBOARD_STATE {
+      display: ptr::null_mut(),
+      __key_down__internal__: [0; 8],
+    };
+    unsafe {
+      al_get_keyboard_state(&mut state);
+    }
+    let is_down = |keycodes: [KeyCode; 2]| {
+      keycodes
+        .iter()
+        .any(|&keycode| unsafe { al_key_down(&state, keycode as i32) } != 0)
+    };
+    let modifiers = [
+      is_down([KeyCode::LShift, KeyCode::RShift]),
+      is_down([KeyCode::LCtrl, KeyCode::RCtrl]),
+      is_down([KeyCode::Alt, KeyCode::AltGr]),
+    ];
+    match modifiers {
+      [true, _, _] => AnnotationColor::Red,
+      [_, true, _] => AnnotationColor::Yellow,
+      [_, _, true] => AnnotationColor::Blue,
+      _ => AnnotationColor::Green,
+    }
+  }
+
+  // the mouse position inside the window
+  fn get_mouse_position(display: &Display) -> (i32, i32) {
+    let (mut x, mut y) = (0, 0);
+    let window_pos = display.get_window_position();
+    unsafe {
+      allegro_sys::mouse::al_get_mouse_cursor_position(&mut x, &mut y);
+    }
+    (x - window_pos.0, y - window_pos.1)
+  }
+
+  fn get_square_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+    let rect = Rect::new(
+      self.rect.x,
+      self.rect.y + BOX_DIMENSION,
+      self.rect.width,
+      self.rect.height,
+    );
+    if !rect.contains_point(x, y) {
+      return None;
+    }
+    let j = ((x - rect.x) / BOX_DIMENSION) as usize;
+    let i = ((y - rect.y) / BOX_DIMENSION) as usize;
+    Some(self.orient(i, j))
+  }
+
+  // the board as it would be with the dragged piece dropped under the
+  // mouse, so the evaluation follows the drag
+  fn get_preview_board(&self, x: f32, y: f32) -> [[PlayerPiece; ROWS]; COLUMNS] {
+    let mut board = self.position.board;
+    if let Some(selected) = self.selected_piece {
+      if let Source::Board { i, j } = selected.source {
+        board[i][j] = Default::default();
+      }
+      if let Some((i, j)) = self.get_square_at(x, y) {
+        board[i][j] = selected.piece;
+      }
+    }
+    board
+  }
+
+  // white's share of the bar grows with the score, big leads squeezed
+  // towards the end by a logistic curve
+  fn draw_evaluation_bar(&self, primitives: &PrimitivesAddon, score: i32) {
+    let share = 1.0 / (1.0 + 10f32.powf(-score as f32 / 400.0));
+    let x = self.rect.x - BAR_WIDTH - BAR_MARGIN;
+    let top = self.rect.y + BOX_DIMENSION;
+    let bottom = top + self.rect.height;
+    primitives.draw_filled_rectangle(x, top, x + BAR_WIDTH, bottom, Color::from_rgb(64, 61, 57));
+    // white's part starts at white's side of the board
+    let (white_top, white_bottom) = if self.player_pov == 1 {
+      (bottom - share * self.rect.height, bottom)
+    } else {
+      (top, top + share * self.rect.height)
+    };
+    primitives.draw_filled_rectangle(
+      x,
+      white_top,
+      x + BAR_WIDTH,
+      white_bottom,
+      Color::from_rgb(240, 240, 240),
+    );
+    let middle = top + self.rect.height / 2.0;
+    primitives.draw_line(
+      x,
+      middle,
+      x + BAR_WIDTH,
+      middle,
+      Color::from_rgb(214, 72, 72),
+      2.0,
+    );
+  }
+
+  fn get_sprite<'b>(player: usize, white: &'b Bitmap, black: &'b Bitmap) -> &'b Bitmap {
+    if player == 1 {
+      white
+    } else {
+      black
+    }
+  }
+
+  // maps a square on the screen to a square on the board and back,
+  // the board is rotated when black is at the bottom
+  fn orient(&self, i: usize, j: usize) -> (usize, usize) {
+    if self.player_pov == 1 {
+      (i, j)
+    } else {
+      (COLUMNS - 1 - i, ROWS - 1 - j)
+    }
+  }
+
   fn draw_boxes(
     &self,
     primitives: &PrimitivesAddon,
@@ -268,26 +603,44 @@ impl Board {
 
   pub fn select_piece_from_shelf(&mut self, x: f32, side: usize) {
     let idx = ((x - self.rect.x) / BOX_DIMENSION) as usize;
-    if (idx > 0) && (idx != COLUMNS - 1) {
-      self.selected_piece = Some(PlayerPiece {
+    if idx == 0 {
+      self.brush = None;
+    } else if idx != COLUMNS - 1 {
+      let piece = PlayerPiece {
         player: side,
         piece_idx: Piece::from_usize(idx - 1),
+      };
+      self.selected_piece = Some(SelectedPiece {
+        piece,
         source: Source::Shelf,
       });
     }
   }
 
+  // the side and cell of the shelf under the point, if any
+  fn get_shelf_cell_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+    if x < self.rect.x || x >= self.rect.x + self.rect.width {
+      return None;
+    }
+    let bottom = self.rect.y + BOX_DIMENSION + self.rect.height;
+    let side = if (self.rect.y..self.rect.y + BOX_DIMENSION).contains(&y) {
+      1 - self.player_pov
+    } else if (bottom..bottom + BOX_DIMENSION).contains(&y) {
+      self.player_pov
+    } else {
+      return None;
+    };
+    Some((side, ((x - self.rect.x) / BOX_DIMENSION) as usize))
+  }
+
   pub fn select_piece_from_board(&mut self, x: f32, y: f32) {
-    let idx_x = ((x - self.rect.x) / BOX_DIMENSION) as usize;
-    let idx_y = (((y - self.rect.y) / BOX_DIMENSION) as usize) - 1;
-    if (idx_x < COLUMNS) && (idx_y < ROWS) {
-      let piece = self.board[idx_y][idx_x];
+    if let Some((idx_y, idx_x)) = self.get_square_at(x, y) {
+      let piece = self.position.board[idx_y][idx_x];
       match piece.piece_idx {
         Piece::None => {}
         _ => {
-          self.selected_piece = Some(PlayerPiece {
-            player: piece.player,
-            piece_idx: piece.piece_idx,
+          self.selected_piece = Some(SelectedPiece {
+            piece,
             source: Source::Board { i: idx_y, j: idx_x },
           });
         }
@@ -295,20 +648,246 @@ impl Board {
     }
   }
 
+  // the picker covers the promotion square and the three squares towards
+  // the middle of the board
+  fn get_promotion_rects(&self, square: (usize, usize)) -> Vec<(Rect, Piece)> {
+    let (k, n) = self.orient(square.0, square.1);
+    PROMOTION_PIECES
+      .iter()
+      .enumerate()
+      .map(|(idx, piece)| {
+        let k = if k == 0 { k + idx } else { k - idx };
+        let rect = Rect::new(
+          self.rect.x + n as f32 * BOX_DIMENSION,
+          self.rect.y + (k + 1) as f32 * BOX_DIMENSION,
+          BOX_DIMENSION,
+          BOX_DIMENSION,
+        );
+        (rect, *piece)
+      })
+      .collect()
+  }
+
+  // the annotations stay with the position the move was played in
+  fn play_move(&mut self, mv: Move) {
+    let annotations = std::mem::take(&mut self.annotations);
+    let before = self.position.clone();
+    self.position.make_move(&mv);
+    self.played_move = Some((before, annotations, mv));
+  }
+
+  // plays the selected piece to the square if that is a legal move, a
+  // promotion waits for the picker first
+  fn play_selected_piece(&mut self, to: (usize, usize)) {
+    let selected = self.selected_piece.take().unwrap();
+    let Source::Board { i, j } = selected.source else {
+      return;
+    };
+    let moves: Vec<Move> = self
+      .position
+      .get_legal_moves()
+      .into_iter()
+      .filter(|mv| mv.from == (i, j) && mv.to == to)
+      // the king can also castle by being dropped on its rook, which is
+      // the only way when it does not move in Chess960
+      .chain(
+        self
+          .position
+          .get_legal_moves()
+          .into_iter()
+          .filter(|mv| mv.from == (i, j) && get_castling_rook(&self.position, mv) == Some(to)),
+      )
+      .collect();
+    match moves.first() {
+      Some(mv) if mv.promotion.is_some() => {
+        self.promotion = Some(Promotion {
+          selected,
+          square: to,
+        })
+      }
+      Some(mv) => self.play_move(*mv),
+      None => {}
+    }
+  }
+
+  fn promote(&mut self, promotion: Promotion, piece: Piece) {
+    if !self.analysis_mode {
+      // a drop in edit mode, from the shelf or from the board
+      self.selected_piece = Some(SelectedPiece {
+        piece: PlayerPiece {
+          piece_idx: piece,
+          ..promotion.selected.piece
+        },
+        ..promotion.selected
+      });
+      self.move_selected_piece(promotion.square.0, promotion.square.1);
+    } else if let Source::Board { i, j } = promotion.selected.source {
+      let mv = self
+        .position
+        .get_legal_moves()
+        .into_iter()
+        .find(|mv| mv.from == (i, j) && mv.to == promotion.square && mv.promotion == Some(piece));
+      if let Some(mv) = mv {
+        self.play_move(mv);
+      }
+    }
+  }
+
   fn move_selected_piece(&mut self, y: usize, x: usize) {
-    let piece = self.selected_piece.unwrap();
-    self.board[y][x] = piece;
-    if let Source::Board { i, j } = piece.source {
-      self.board[i][j] = PlayerPiece {
-        piece_idx: Piece::None,
-        ..piece
-      };
+    let selected = self.selected_piece.unwrap();
+    let piece = selected.piece;
+    // pawns cannot stand on the back rows, they become the piece picked
+    if piece.piece_idx == Piece::Pawn && (y == 0 || y == COLUMNS - 1) {
+      self.promotion = Some(Promotion {
+        selected,
+        square: (y, x),
+      });
+      self.selected_piece = None;
+      return;
+    }
+    if let Source::Board { i, j } = selected.source {
+      self.position.remove_piece((i, j));
     }
+    self
+      .position
+      .put_piece((y, x), piece.player, piece.piece_idx);
+    self.position.update_en_passant();
     self.selected_piece = None;
   }
 
+  // a `b` is the black bishop unless a rank follows
+  fn resolve_typed_file(&mut self) {
+    if self.typed_file.take() == Some('b') {
+      self.typed_piece = Some(PlayerPiece {
+        player: 0,
+        piece_idx: Piece::Bishop,
+      });
+    }
+  }
+
+  fn place_piece(&mut self, piece: Option<PlayerPiece>, square: (usize, usize)) {
+    if let Some(piece) = piece {
+      self.selected_piece = Some(SelectedPiece {
+        piece,
+        source: Source::Shelf,
+      });
+      self.move_selected_piece(square.0, square.1);
+    }
+  }
+
+  // a click with a brush takes its piece off a square that holds it
+  // and puts it on any other
+  fn stamp_brush(&mut self, square: (usize, usize)) {
+    match (self.position.get_piece(square), self.brush) {
+      (Some((player, piece)), Some(brush))
+        if player == brush.player && piece == brush.piece_idx =>
+      {
+        self.position.remove_piece(square);
+        self.position.update_en_passant();
+      }
+      _ => self.place_piece(self.brush, square),
+    }
+  }
+
+  // moves the cursor the way it looks on the screen
+  fn move_cursor(&mut self, rows: isize, columns: isize) {
+    let (k, n) = self.orient(self.cursor.0, self.cursor.1);
+    let k = k.saturating_add_signed(rows).min(COLUMNS - 1);
+    let n = n.saturating_add_signed(columns).min(ROWS - 1);
+    self.cursor = self.orient(k, n);
+  }
+
+  // FEN letters pick a piece, a typed square or Enter places it at the
+  // cursor, the arrows move the cursor and Delete empties its square,
+  // left and right are left to the move list while a game is loaded
+  fn keyboard_listener(&mut self, keycode: KeyCode, character: char) -> bool {
+    let rank = character.to_digit(10).filter(|rank| (1..=8).contains(rank));
+    if let (Some(file), Some(rank)) = (self.typed_file, rank) {
+      self.typed_file = None;
+      self.cursor = (COLUMNS - rank as usize, file as usize - 'a' as usize);
+      self.place_piece(self.typed_piece, self.cursor);
+      return true;
+    }
+    self.resolve_typed_file();
+    match keycode {
+      KeyCode::Enter | KeyCode::PadEnter => self.place_piece(self.typed_piece, self.cursor),
+      KeyCode::Delete | KeyCode::Backspace => {
+        self.position.remove_piece(self.cursor);
+        self.position.update_en_passant();
+      }
+      KeyCode::Escape => {
+        self.typed_piece = None;
+        self.brush = None;
+      }
+      KeyCode::Left | KeyCode::Right if self.is_game_loaded => return false,
+      KeyCode::Left => self.move_cursor(0, -1),
+      KeyCode::Right => self.move_cursor(0, 1),
+      KeyCode::Up => self.move_cursor(-1, 0),
+      KeyCode::Down => self.move_cursor(1, 0),
+      _ => match character {
+        'a'..='h' => self.typed_file = Some(character),
+        _ => match get_piece_for_character(character) {
+          Some(piece) => {
+            self.typed_piece = Some(PlayerPiece {
+              player: character.is_ascii_uppercase() as usize,
+              piece_idx: piece,
+            })
+          }
+          None => return false,
+        },
+      },
+    }
+    true
+  }
+
   pub fn event_listener(&mut self, event: &Event) -> bool {
     match event {
+      // the right button draws arrows and highlights
+      MouseButtonDown {
+        x, y, button: 2, ..
+      } => {
+        self.annotation_start = self.get_square_at(*x as f32, *y as f32);
+        return self.annotation_start.is_some();
+      }
+      MouseButtonUp {
+        x, y, button: 2, ..
+      } => {
+        let Some(from) = self.annotation_start.take() else {
+          return false;
+        };
+        let color = Board::get_modifier_color();
+        match self.get_square_at(*x as f32, *y as f32) {
+          Some(to) if to == from => self.annotations.toggle_highlight(from, color),
+          Some(to) => self.annotations.toggle_arrow(from, to, color),
+          None => {}
+        }
+        return true;
+      }
+      MouseButtonDown { button, .. } | MouseButtonUp { button, .. } if *button != 1 => {}
+      Event::KeyChar {
+        keycode, unichar, ..
+      } if self.has_keyboard_focus && self.promotion.is_none() => {
+        return self.keyboard_listener(*keycode, *unichar);
+      }
+      KeyDown {
+        keycode: KeyCode::Escape,
+        ..
+      } if self.promotion.is_some() => {
+        self.promotion = None;
+        return true;
+      }
+      MouseButtonDown { x, y, .. } if self.promotion.is_some() => {
+        // any click closes the picker, one outside it cancels the move
+        let promotion = self.promotion.take().unwrap();
+        let choice = self
+          .get_promotion_rects(promotion.square)
+          .into_iter()
+          .find(|(rect, _)| rect.contains_point(*x as f32, *y as f32));
+        if let Some((_, piece)) = choice {
+          self.promote(promotion, piece);
+        }
+        return true;
+      }
       MouseButtonDown { x, y, .. } => {
         let x_f32 = *x as f32;
         let y_f32 = *y as f32;
@@ -319,18 +898,41 @@ impl Board {
           self.rect.width,
           BOX_DIMENSION * ((COLUMNS + 2) as f32),
         );
-        if temp_rect.contains_point(x_f32, y_f32) {
+        if temp_rect.contains_point(x_f32, y_f32) && self.analysis_mode {
+          // only the side to move can be picked up, the shelves are
+          // out of play
+          if self.get_square_at(x_f32, y_f32).is_none() {
+            return true;
+          }
+          self.select_piece_from_board(x_f32, y_f32);
+          if self
+            .selected_piece
+            .is_some_and(|selected| selected.piece.player != self.position.player)
+          {
+            self.selected_piece = None;
+          }
+          return true;
+        } else if temp_rect.contains_point(x_f32, y_f32) {
+          self.has_keyboard_focus = true;
+          self.shelf_press = self.get_shelf_cell_at(x_f32, y_f32);
+          if let Some(square) = self.get_square_at(x_f32, y_f32) {
+            self.cursor = square;
+          }
           if y_f32 < self.rect.x + BOX_DIMENSION {
-            self.select_piece_from_shelf(x_f32, 0);
+            self.select_piece_from_shelf(x_f32, 1 - self.player_pov);
           } else if y_f32 > temp_rect.x + temp_rect.height - BOX_DIMENSION {
-            self.select_piece_from_shelf(x_f32, 1);
+            self.select_piece_from_shelf(x_f32, self.player_pov);
+          } else if self.brush.is_some() {
+            self.stamp_brush(self.cursor);
           } else {
             self.select_piece_from_board(x_f32, y_f32);
           }
           return true;
         }
+        self.has_keyboard_focus = false;
       }
       MouseButtonUp { x, y, .. } => {
+        let shelf_press = self.shelf_press.take();
         if self.selected_piece.is_some() {
           let y_f32 = *y as f32;
           let x_f32 = *x as f32;
@@ -344,10 +946,27 @@ impl Board {
           if temp_rect.contains_point(x_f32, y_f32) {
             let x_idx = ((x_f32 - self.rect.x) / BOX_DIMENSION) as usize;
             let y_idx = ((y_f32 - temp_rect.y) / BOX_DIMENSION) as usize;
-            self.move_selected_piece(y_idx, x_idx);
+            let (y_idx, x_idx) = self.orient(y_idx, x_idx);
+            if self.analysis_mode {
+              self.play_selected_piece((y_idx, x_idx));
+            } else {
+              self.move_selected_piece(y_idx, x_idx);
+            }
+          } else if self.analysis_mode {
+            self.selected_piece = None;
           } else {
-            if let Source::Board { i, j } = self.selected_piece.unwrap().source {
-              self.board[i][j].piece_idx = Piece::None;
+            let selected = self.selected_piece.unwrap();
+            match selected.source {
+              Source::Board { i, j } => {
+                self.position.remove_piece((i, j));
+                self.position.update_en_passant();
+              }
+              // a click on a shelf piece, not a drag, arms the brush
+              Source::Shelf => {
+                if shelf_press.is_some() && shelf_press == self.get_shelf_cell_at(x_f32, y_f32) {
+                  self.brush = Some(selected.piece);
+                }
+              }
             }
             self.selected_piece = None;
           }
@@ -359,6 +978,26 @@ impl Board {
     false
   }
 
+  // the space under the bottom shelf
+  pub fn get_footer_rect(&self, height: f32) -> Rect {
+    Rect::new(
+      self.rect.x,
+      self.rect.y + self.rect.height + BOX_DIMENSION * 2.0 + 20.0,
+      self.rect.width,
+      height,
+    )
+  }
+
+  // the space over the top shelf
+  pub fn get_toolbar_rect(&self, height: f32) -> Rect {
+    Rect::new(
+      self.rect.x,
+      self.rect.y - height - 20.0,
+      self.rect.width,
+      height,
+    )
+  }
+
   pub fn get_dropdown_rect(&self, width: f32, height: f32) -> Rect {
     Rect::new(
       self.rect.x + (BOX_DIMENSION * COLUMNS as f32) + 30.0,
@@ -369,49 +1008,153 @@ impl Board {
   }
 
   pub fn set_starting_position(&mut self) {
-    let pieces = [
-      Piece::Rook,
-      Piece::Knight,
-      Piece::Bishop,
-      Piece::Queen,
-      Piece::King,
-      Piece::Bishop,
-      Piece::Knight,
-      Piece::Rook,
-    ];
-    self.clear_board();
-    for i in 0..COLUMNS {
-      if (i == 0) || (i == COLUMNS - 1) {
-        self.board[i] = pieces.map(|piece: Piece| -> PlayerPiece {
-          PlayerPiece {
-            player: (i != 0) as usize,
-            piece_idx: piece,
-            source: Source::Shelf,
-          }
-        });
-      }
-      if (i == 1) || (i == COLUMNS - 2) {
-        for j in 0..ROWS {
-          self.board[i][j] = PlayerPiece {
-            piece_idx: Piece::Pawn,
-            source: Source::Shelf,
-            player: (i != 1) as usize,
-          }
-        }
-      }
-    }
+    self.position.set_starting_position();
+    self.selected_piece = None;
+    self.promotion = None;
+    self.annotations.clear();
+  }
+
+  pub fn set_chess960_position(&mut self, number: u32) {
+    self.position.set_chess960_position(number);
+    self.selected_piece = None;
+    self.promotion = None;
+    self.annotations.clear();
   }
 
   pub fn flip_board(&mut self) {
     self.player_pov = if self.player_pov == 1 { 0 } else { 1 };
   }
+
+  pub fn mirror_files(&mut self) {
+    self.set_position(mirror_files(&self.position));
+  }
+
+  pub fn swap_colours(&mut self) {
+    self.set_position(swap_colours(&self.position));
+  }
+
+  // shifts the pieces the way they look on the screen, so up is towards
+  // the top of the board even when it is flipped, false when a piece
+  // would fall off
+  pub fn shift_pieces(&mut self, rows: isize, columns: isize) -> bool {
+    let (rows, columns) = if self.player_pov == 1 {
+      (rows, columns)
+    } else {
+      (-rows, -columns)
+    };
+    match shift_pieces(&self.position, rows, columns) {
+      Some(position) => {
+        self.set_position(position);
+        true
+      }
+      None => false,
+    }
+  }
+
   pub fn clear_board(&mut self) {
-    self.board = Default::default();
+    self.position.clear_board();
+    self.selected_piece = None;
+    self.promotion = None;
+    self.annotations.clear();
+  }
+
+  pub fn set_move_line(&mut self, moves: &[Move]) {
+    self.move_line = moves.iter().map(|mv| (mv.from, mv.to)).collect();
+  }
+
+  pub fn set_analysis_mode(&mut self, analysis_mode: bool) {
+    self.analysis_mode = analysis_mode;
+    self.selected_piece = None;
+    self.promotion = None;
+    self.has_keyboard_focus = false;
+    self.brush = None;
+    self.typed_piece = None;
+  }
+
+  pub fn set_game_loaded(&mut self, is_game_loaded: bool) {
+    self.is_game_loaded = is_game_loaded;
+  }
+
+  // true while the promotion picker waits for a piece
+  pub fn is_promoting(&self) -> bool {
+    self.promotion.is_some()
+  }
+
+  pub fn is_analysis_mode(&self) -> bool {
+    self.analysis_mode
+  }
+
+  // the move played since the last call, with the position it was
+  // played in and that position's annotations
+  pub fn take_played_move(&mut self) -> Option<(Position, Annotations, Move)> {
+    self.played_move.take()
   }
 
-  pub fn generate_fen(&self, castle_white: (bool, bool), castle_black: (bool, bool)) {
-    let notation = generate_fen_from_board(self.board, self.player_pov, castle_white, castle_black);
+  pub fn get_position(&self) -> &Position {
+    &self.position
+  }
+
+  pub fn get_position_mut(&mut self) -> &mut Position {
+    &mut self.position
+  }
+
+  pub fn set_position(&mut self, position: Position) {
+    self.position = position;
+    self.selected_piece = None;
+    self.promotion = None;
+    self.annotations.clear();
+  }
+
+  pub fn get_annotations(&self) -> &Annotations {
+    &self.annotations
+  }
+
+  // call after `set_position`, which clears them
+  pub fn set_annotations(&mut self, annotations: Annotations) {
+    self.annotations = annotations;
+  }
+
+  pub fn get_fen(&self) -> String {
+    self.position.to_fen()
+  }
+
+  pub fn set_castling_notation(&mut self, notation: CastlingNotation) {
+    self.castling_notation = notation;
+  }
+
+  pub fn generate_fen(&self) {
+    let notation = self.position.to_fen_with(self.castling_notation);
     let mut ctx: ClipboardContext = ClipboardProvider::new().unwrap();
     ctx.set_contents(notation).unwrap();
   }
+
+  pub fn load_fen(&mut self, fen: &str) -> Result<(), FenError> {
+    self.set_position(Position::from_fen(fen)?);
+    Ok(())
+  }
+
+  pub fn get_pgn(&self, tags: &PgnTags) -> String {
+    generate_pgn(&self.position, tags, &self.annotations)
+  }
+
+  pub fn copy_pgn(&self, tags: &PgnTags) -> Result<(), Box<dyn Error>> {
+    let mut ctx: ClipboardContext = ClipboardProvider::new()?;
+    ctx.set_contents(self.get_pgn(tags))?;
+    Ok(())
+  }
+
+  pub fn save_pgn(&self, tags: &PgnTags, path: &str) -> Result<(), Box<dyn Error>> {
+    if path.is_empty() {
+      return Err("no file name given".into());
+    }
+    fs::write(path, self.get_pgn(tags))?;
+    Ok(())
+  }
+
+  pub fn paste_fen(&mut self) -> Result<(), Box<dyn Error>> {
+    let mut ctx: ClipboardContext = ClipboardProvider::new()?;
+    let contents = ctx.get_contents()?;
+    self.load_fen(contents.trim())?;
+    Ok(())
+  }
 }
@@ -13,6 +13,7 @@ pub struct CheckBox<'a> {
   curr_state: bool,
   text_pos_x: f32,
   is_hovering: bool,
+  is_disabled: bool,
 }
 
 impl<'a> CheckBox<'a> {
@@ -23,13 +24,18 @@ impl<'a> CheckBox<'a> {
       curr_state: false,
       text_pos_x: 0.0,
       is_hovering: false,
+      is_disabled: false,
     };
     checkbox.text_pos_x = checkbox.rect.x + checkbox.rect.width + BORDER_WIDTH + 10.0;
     checkbox
   }
 
   pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
-    let text_color = Color::from_rgb(130, 130, 143);
+    let text_color = if self.is_disabled {
+      Color::from_rgb(70, 70, 77)
+    } else {
+      Color::from_rgb(130, 130, 143)
+    };
     primitives.draw_rounded_rectangle(
       self.rect.x - BORDER_WIDTH,
       self.rect.y - BORDER_WIDTH,
@@ -37,7 +43,7 @@ impl<'a> CheckBox<'a> {
       self.rect.height + self.rect.y + BORDER_WIDTH,
       3.0,
       3.0,
-      if self.is_hovering {
+      if self.is_hovering && !self.is_disabled {
         Color::from_rgb(177, 177, 185)
       } else {
         text_color
@@ -81,7 +87,7 @@ impl<'a> CheckBox<'a> {
         return self.is_hovering;
       }
       Event::MouseButtonDown { .. } => {
-        if self.is_hovering {
+        if self.is_hovering && !self.is_disabled {
           self.curr_state = !self.curr_state;
         }
         return self.is_hovering;
@@ -94,6 +100,18 @@ impl<'a> CheckBox<'a> {
   pub fn is_checked(&self) -> bool {
     self.curr_state
   }
+
+  pub fn set_checked(&mut self, state: bool) {
+    self.curr_state = state;
+  }
+
+  pub fn set_disabled(&mut self, disabled: bool) {
+    self.is_disabled = disabled;
+  }
+
+  pub fn get_next_y(&self) -> f32 {
+    self.rect.y + self.rect.height + BORDER_WIDTH
+  }
 }
 
 pub struct CheckBoxGroup<'a> {
@@ -170,4 +188,18 @@ impl<'a> CheckBoxGroup<'a> {
       (false, false)
     }
   }
+
+  pub fn set_enabled(&mut self, values: (bool, bool)) {
+    if self.components.len() == 2 {
+      self.components[0].set_disabled(!values.0);
+      self.components[1].set_disabled(!values.1);
+    }
+  }
+
+  pub fn set_values(&mut self, values: (bool, bool)) {
+    if self.components.len() == 2 {
+      self.components[0].set_checked(values.0);
+      self.components[1].set_checked(values.1);
+    }
+  }
 }
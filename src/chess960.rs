@@ -0,0 +1,58 @@
+use crate::{
+  position::{Piece, ROWS},
+  random::Random,
+};
+
+pub const POSITION_COUNT: u32 = 960;
+// the number of the classical start position
+pub const CLASSICAL_NUMBER: u32 = 518;
+
+// the files two knights can take among the five squares left after the
+// bishops and the queen, in the order the numbering uses
+const KNIGHT_FILES: [(usize, usize); 10] = [
+  (0, 1),
+  (0, 2),
+  (0, 3),
+  (0, 4),
+  (1, 2),
+  (1, 3),
+  (1, 4),
+  (2, 3),
+  (2, 4),
+  (3, 4),
+];
+
+// the back rank of the start position with the given number, using the
+// Scharnagl numbering from 0 to 959, numbers past that wrap around
+pub fn get_back_rank(number: u32) -> [Piece; ROWS] {
+  let mut number = (number % POSITION_COUNT) as usize;
+  let mut pieces = [Piece::None; ROWS];
+  // one bishop on the light files b, d, f, h and one on the dark ones
+  pieces[number % 4 * 2 + 1] = Piece::Bishop;
+  number /= 4;
+  pieces[number % 4 * 2] = Piece::Bishop;
+  number /= 4;
+  let mut place_on_free = |idx: usize, piece: Piece| {
+    let file = (0..ROWS)
+      .filter(|&j| pieces[j] == Piece::None)
+      .nth(idx)
+      .unwrap();
+    pieces[file] = piece;
+  };
+  place_on_free(number % 6, Piece::Queen);
+  number /= 6;
+  let (first, second) = KNIGHT_FILES[number];
+  // the second knight goes first so the first one's index still counts
+  // the same free squares
+  place_on_free(second, Piece::Knight);
+  place_on_free(first, Piece::Knight);
+  // the king always stands between the rooks
+  for piece in [Piece::Rook, Piece::King, Piece::Rook] {
+    place_on_free(0, piece);
+  }
+  pieces
+}
+
+pub fn get_random_number() -> u32 {
+  Random::new().get_below(POSITION_COUNT as usize) as u32
+}
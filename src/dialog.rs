@@ -0,0 +1,159 @@
+use crate::{
+  button::Button,
+  checkbox::CheckBox,
+  generator::{generate_position, parse_material},
+  input::TextInput,
+  position::Position,
+  Rect,
+};
+use allegro::{Color, Core, Event, KeyCode};
+use allegro_font::{Font, FontAlign, FontDrawing};
+use allegro_primitives::PrimitivesAddon;
+
+const PADDING: f32 = 20.0;
+
+// asks for the material of a random position, drawn over everything and
+// taking every event while it is open
+pub struct GeneratorDialog<'a> {
+  rect: Rect,
+  material: TextInput<'a>,
+  white_to_move: CheckBox<'a>,
+  buttons: [Button<'a>; 2],
+  error: Option<String>,
+  is_open: bool,
+}
+
+impl<'a> GeneratorDialog<'a> {
+  pub fn new(rect: Rect, input_height: f32, font: &Font) -> GeneratorDialog<'a> {
+    let (x, width) = (rect.x + PADDING, rect.width - PADDING * 2.0);
+    let line_height = font.get_line_height() as f32;
+    let material = TextInput::new(
+      Rect::new(x, rect.y + PADDING + line_height * 2.0, width, input_height),
+      "Material, like KRP vs KR or 5 random pieces",
+      "KRP vs KR",
+      font,
+    );
+    let mut white_to_move = CheckBox::new(x, material.get_next_y() + 20.0, 20.0, "White to move");
+    white_to_move.set_checked(true);
+    let buttons_y = white_to_move.get_next_y() + 20.0;
+    let half_width = (width - 10.0) / 2.0;
+    let buttons = [
+      Button::new(
+        Rect::new(x, buttons_y, half_width, input_height),
+        "GENERATE",
+        font,
+      ),
+      Button::new(
+        Rect::new(x + half_width + 10.0, buttons_y, half_width, input_height),
+        "CANCEL",
+        font,
+      ),
+    ];
+    GeneratorDialog {
+      rect,
+      material,
+      white_to_move,
+      buttons,
+      error: None,
+      is_open: false,
+    }
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    if !self.is_open {
+      return;
+    }
+    let rect = &self.rect;
+    primitives.draw_filled_rounded_rectangle(
+      rect.x,
+      rect.y,
+      rect.x + rect.width,
+      rect.y + rect.height,
+      5.0,
+      5.0,
+      Color::from_rgb(30, 29, 26),
+    );
+    primitives.draw_rounded_rectangle(
+      rect.x,
+      rect.y,
+      rect.x + rect.width,
+      rect.y + rect.height,
+      5.0,
+      5.0,
+      Color::from_rgb(82, 82, 82),
+      2.0,
+    );
+    core.draw_text(
+      font,
+      Color::from_rgb(255, 255, 255),
+      rect.x + PADDING,
+      rect.y + PADDING,
+      FontAlign::Left,
+      "Random position",
+    );
+    self.material.draw(core, primitives, font);
+    self.white_to_move.draw(core, primitives, font);
+    for button in self.buttons.iter() {
+      button.draw(core, primitives, font);
+    }
+    if let Some(error) = &self.error {
+      core.draw_text(
+        font,
+        Color::from_rgb(224, 108, 117),
+        rect.x + PADDING,
+        rect.y + rect.height - PADDING - font.get_line_height() as f32,
+        FontAlign::Left,
+        error.as_str(),
+      );
+    }
+  }
+
+  // the generated position once GENERATE found one, which also closes
+  // the dialog
+  pub fn event_listener(&mut self, event: &Event) -> Option<Position> {
+    if let Event::KeyDown {
+      keycode: KeyCode::Escape,
+      ..
+    } = event
+    {
+      self.is_open = false;
+      return None;
+    }
+    self.material.event_listener(event);
+    self.white_to_move.event_listener(event);
+    if self.buttons[1].event_listener(event) {
+      self.is_open = false;
+      return None;
+    }
+    if !self.buttons[0].event_listener(event) {
+      return None;
+    }
+    let player = self.white_to_move.is_checked() as usize;
+    let material = match parse_material(self.material.get_text()) {
+      Ok(material) => material,
+      Err(error) => {
+        self.error = Some(format!("Could not read the material: {}", error));
+        return None;
+      }
+    };
+    match generate_position(&material, player) {
+      Some(position) => {
+        self.is_open = false;
+        Some(position)
+      }
+      None => {
+        self.error = Some("No legal position has that material".to_string());
+        None
+      }
+    }
+  }
+
+  pub fn open(&mut self) {
+    self.is_open = true;
+    self.error = None;
+  }
+
+  pub fn is_open(&self) -> bool {
+    self.is_open
+  }
+}
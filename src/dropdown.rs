@@ -162,4 +162,21 @@ impl<'a> Dropdown<'a> {
   pub fn get_selected_item_idx(&self) -> i32 {
     self.selected_idx
   }
+
+  pub fn set_items(&mut self, items: Vec<&'a str>) {
+    self.items = items;
+    self.hover_element_idx = 0;
+    if self.selected_idx >= self.items.len() as i32 {
+      self.selected_idx = self.items.len() as i32 - 1;
+    }
+  }
+
+  pub fn is_focused(&self) -> bool {
+    self.is_focused
+  }
+
+  pub fn set_selected_item_idx(&mut self, idx: i32) {
+    assert!(idx == -1 || self.items.len() > idx as usize);
+    self.selected_idx = idx;
+  }
 }
@@ -0,0 +1,240 @@
+use crate::{
+  movegen::make_move,
+  position::Position,
+  san::{get_san, parse_uci_move},
+};
+use std::{
+  fmt,
+  io::{self, BufRead, BufReader, Write},
+  process::{Child, ChildStdin, Command, Stdio},
+  sync::mpsc::{self, Receiver, TryRecvError},
+  thread,
+};
+
+// the longest part of the principal variation that gets shown
+const MAX_PV_LENGTH: usize = 12;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Score {
+  Centipawns(i32),
+  // moves to mate, negative when the side to move gets mated
+  Mate(i32),
+}
+
+impl Score {
+  // engines score for the side to move, this gives the score for white
+  pub fn for_white(self, player: usize) -> Score {
+    if player == 1 {
+      return self;
+    }
+    match self {
+      Score::Centipawns(value) => Score::Centipawns(-value),
+      Score::Mate(moves) => Score::Mate(-moves),
+    }
+  }
+}
+
+impl fmt::Display for Score {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Score::Centipawns(value) => write!(f, "{:+.2}", *value as f32 / 100.0),
+      Score::Mate(moves) if *moves < 0 => write!(f, "-#{}", -moves),
+      Score::Mate(moves) => write!(f, "#{}", moves),
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EngineInfo {
+  pub depth: u32,
+  pub score: Option<Score>,
+  // moves in UCI notation, like `e2e4` or `e7e8q`
+  pub pv: Vec<String>,
+}
+
+// reads the parts of an `info` line that get shown, lines without a
+// score and a principal variation are skipped
+pub fn parse_info(line: &str) -> Option<EngineInfo> {
+  let mut tokens = line.split_whitespace();
+  if tokens.next() != Some("info") {
+    return None;
+  }
+  let mut info = EngineInfo::default();
+  while let Some(token) = tokens.next() {
+    match token {
+      "depth" => info.depth = tokens.next()?.parse().ok()?,
+      "multipv" if tokens.next()? != "1" => return None,
+      "score" => {
+        let kind = tokens.next()?;
+        let value = tokens.next()?.parse().ok()?;
+        info.score = match kind {
+          "cp" => Some(Score::Centipawns(value)),
+          "mate" => Some(Score::Mate(value)),
+          _ => None,
+        };
+      }
+      "pv" => info.pv = tokens.by_ref().map(String::from).collect(),
+      // the rest of the line is free text
+      "string" => break,
+      _ => {}
+    }
+  }
+  match (info.score, info.pv.is_empty()) {
+    (Some(_), false) => Some(info),
+    _ => None,
+  }
+}
+
+// the principal variation in SAN with move numbers, cut short at the
+// first move that is not legal
+pub fn get_pv_san(position: &Position, pv: &[String]) -> String {
+  let mut position = position.clone();
+  let mut line = String::new();
+  for (idx, uci) in pv.iter().take(MAX_PV_LENGTH).enumerate() {
+    let Some(mv) = parse_uci_move(&position, uci) else {
+      break;
+    };
+    if position.player == 1 {
+      line += format!("{}. ", position.fullmove_number).as_str();
+    } else if idx == 0 {
+      line += format!("{}... ", position.fullmove_number).as_str();
+    }
+    line += get_san(&position, &mv).as_str();
+    line.push(' ');
+    make_move(&mut position, &mv);
+  }
+  line.trim_end().to_string()
+}
+
+// a UCI engine running in its own process, its output is read on a
+// separate thread so the editor never waits for it
+pub struct Engine {
+  child: Child,
+  stdin: ChildStdin,
+  lines: Receiver<String>,
+  name: String,
+  info: Option<EngineInfo>,
+  is_running: bool,
+  is_searching: bool,
+  // whether the engine was told the position is Chess960
+  chess960: bool,
+  // searches that were stopped but have not sent their bestmove yet,
+  // their info lines belong to an old position
+  stopped_searches: usize,
+}
+
+impl Engine {
+  pub fn start(path: &str) -> io::Result<Engine> {
+    let mut child = Command::new(path)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+      for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if sender.send(line).is_err() {
+          break;
+        }
+      }
+    });
+    let mut engine = Engine {
+      child,
+      stdin,
+      lines,
+      name: path.to_string(),
+      info: None,
+      is_running: true,
+      is_searching: false,
+      chess960: false,
+      stopped_searches: 0,
+    };
+    engine.send("uci")?;
+    engine.send("isready")?;
+    Ok(engine)
+  }
+
+  fn send(&mut self, command: &str) -> io::Result<()> {
+    writeln!(self.stdin, "{}", command)?;
+    self.stdin.flush()
+  }
+
+  pub fn analyse(&mut self, fen: &str, chess960: bool) -> io::Result<()> {
+    self.stop()?;
+    if chess960 != self.chess960 {
+      self.send(format!("setoption name UCI_Chess960 value {}", chess960).as_str())?;
+      self.chess960 = chess960;
+    }
+    self.send(format!("position fen {}", fen).as_str())?;
+    self.send("go infinite")?;
+    self.is_searching = true;
+    Ok(())
+  }
+
+  pub fn stop(&mut self) -> io::Result<()> {
+    self.info = None;
+    if self.is_searching {
+      self.send("stop")?;
+      self.stopped_searches += 1;
+      self.is_searching = false;
+    }
+    Ok(())
+  }
+
+  // reads everything the engine sent since the last call, true when
+  // there is something new to show
+  pub fn poll(&mut self) -> bool {
+    let mut changed = false;
+    loop {
+      let line = match self.lines.try_recv() {
+        Ok(line) => line,
+        Err(TryRecvError::Empty) => break,
+        Err(TryRecvError::Disconnected) => {
+          changed |= self.is_running;
+          self.is_running = false;
+          break;
+        }
+      };
+      if let Some(name) = line.strip_prefix("id name ") {
+        self.name = name.to_string();
+        changed = true;
+      } else if line.starts_with("bestmove") {
+        if self.stopped_searches > 0 {
+          self.stopped_searches -= 1;
+        } else {
+          self.is_searching = false;
+        }
+      } else if let Some(info) = parse_info(line.as_str()) {
+        if self.stopped_searches == 0 {
+          self.info = Some(info);
+          changed = true;
+        }
+      }
+    }
+    changed
+  }
+
+  pub fn get_name(&self) -> &str {
+    self.name.as_str()
+  }
+
+  // the latest info for the position being analysed
+  pub fn get_info(&self) -> Option<&EngineInfo> {
+    self.info.as_ref()
+  }
+
+  // false once the engine closed its output, usually because it exited
+  pub fn is_running(&self) -> bool {
+    self.is_running
+  }
+}
+
+impl Drop for Engine {
+  fn drop(&mut self) {
+    let _ = self.send("quit");
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
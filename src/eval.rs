@@ -0,0 +1,147 @@
+use crate::position::{Piece, PlayerPiece, COLUMNS, ROWS};
+
+// piece-square tables from the simplified evaluation function, written
+// from white's side with the eighth rank first like the board array
+const PAWN_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [0, 0, 0, 0, 0, 0, 0, 0],
+  [50, 50, 50, 50, 50, 50, 50, 50],
+  [10, 10, 20, 30, 30, 20, 10, 10],
+  [5, 5, 10, 25, 25, 10, 5, 5],
+  [0, 0, 0, 20, 20, 0, 0, 0],
+  [5, -5, -10, 0, 0, -10, -5, 5],
+  [5, 10, 10, -20, -20, 10, 10, 5],
+  [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const KNIGHT_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [-50, -40, -30, -30, -30, -30, -40, -50],
+  [-40, -20, 0, 0, 0, 0, -20, -40],
+  [-30, 0, 10, 15, 15, 10, 0, -30],
+  [-30, 5, 15, 20, 20, 15, 5, -30],
+  [-30, 0, 15, 20, 20, 15, 0, -30],
+  [-30, 5, 10, 15, 15, 10, 5, -30],
+  [-40, -20, 0, 5, 5, 0, -20, -40],
+  [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+const BISHOP_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [-20, -10, -10, -10, -10, -10, -10, -20],
+  [-10, 0, 0, 0, 0, 0, 0, -10],
+  [-10, 0, 5, 10, 10, 5, 0, -10],
+  [-10, 5, 5, 10, 10, 5, 5, -10],
+  [-10, 0, 10, 10, 10, 10, 0, -10],
+  [-10, 10, 10, 10, 10, 10, 10, -10],
+  [-10, 5, 0, 0, 0, 0, 5, -10],
+  [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+const ROOK_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [0, 0, 0, 0, 0, 0, 0, 0],
+  [5, 10, 10, 10, 10, 10, 10, 5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [-5, 0, 0, 0, 0, 0, 0, -5],
+  [0, 0, 0, 5, 5, 0, 0, 0],
+];
+
+const QUEEN_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [-20, -10, -10, -5, -5, -10, -10, -20],
+  [-10, 0, 0, 0, 0, 0, 0, -10],
+  [-10, 0, 5, 5, 5, 5, 0, -10],
+  [-5, 0, 5, 5, 5, 5, 0, -5],
+  [0, 0, 5, 5, 5, 5, 0, -5],
+  [-10, 5, 5, 5, 5, 5, 0, -10],
+  [-10, 0, 5, 0, 0, 0, 0, -10],
+  [-20, -10, -10, -5, -5, -10, -10, -20],
+];
+
+const KING_TABLE: [[i32; ROWS]; COLUMNS] = [
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-30, -40, -40, -50, -50, -40, -40, -30],
+  [-20, -30, -30, -40, -40, -30, -30, -20],
+  [-10, -20, -20, -20, -20, -20, -20, -10],
+  [20, 20, 0, 0, 0, 0, 20, 20],
+  [20, 30, 10, 0, 0, 10, 30, 20],
+];
+
+const SHIELD_BONUS: i32 = 10;
+const OPEN_FILE_PENALTY: i32 = 20;
+
+fn get_value(piece: Piece) -> i32 {
+  match piece {
+    Piece::Pawn => 100,
+    Piece::Knight => 320,
+    Piece::Bishop => 330,
+    Piece::Rook => 500,
+    Piece::Queen => 900,
+    _ => 0,
+  }
+}
+
+fn get_table(piece: Piece) -> Option<&'static [[i32; ROWS]; COLUMNS]> {
+  match piece {
+    Piece::Pawn => Some(&PAWN_TABLE),
+    Piece::Knight => Some(&KNIGHT_TABLE),
+    Piece::Bishop => Some(&BISHOP_TABLE),
+    Piece::Rook => Some(&ROOK_TABLE),
+    Piece::Queen => Some(&QUEEN_TABLE),
+    Piece::King => Some(&KING_TABLE),
+    Piece::None => None,
+  }
+}
+
+// own pawns on the two rows in front of the king and its neighbouring
+// files, and a penalty when the king's own file has none of them
+fn get_king_safety(
+  board: &[[PlayerPiece; ROWS]; COLUMNS],
+  player: usize,
+  king: (usize, usize),
+) -> i32 {
+  let forward: i32 = if player == 1 { -1 } else { 1 };
+  let rows: Vec<usize> = (1..=2)
+    .map(|distance| king.0 as i32 + forward * distance)
+    .filter(|row| (0..COLUMNS as i32).contains(row))
+    .map(|row| row as usize)
+    .collect();
+  let is_shielded = |file: usize| {
+    rows.iter().any(|&row| {
+      let piece = board[row][file];
+      piece.piece_idx == Piece::Pawn && piece.player == player
+    })
+  };
+  (king.1.saturating_sub(1)..=(king.1 + 1).min(ROWS - 1))
+    .map(|file| match is_shielded(file) {
+      true => SHIELD_BONUS,
+      false if file == king.1 => -OPEN_FILE_PENALTY,
+      false => 0,
+    })
+    .sum()
+}
+
+// a rough score in centipawns, positive when white is better
+pub fn evaluate(board: &[[PlayerPiece; ROWS]; COLUMNS]) -> i32 {
+  let mut score = 0;
+  for (i, row) in board.iter().enumerate() {
+    for (j, piece) in row.iter().enumerate() {
+      let Some(table) = get_table(piece.piece_idx) else {
+        continue;
+      };
+      // black reads the tables upside down
+      let (value, sign) = if piece.player == 1 {
+        (table[i][j], 1)
+      } else {
+        (table[COLUMNS - 1 - i][j], -1)
+      };
+      let mut piece_score = get_value(piece.piece_idx) + value;
+      if piece.piece_idx == Piece::King {
+        piece_score += get_king_safety(board, piece.player, (i, j));
+      }
+      score += sign * piece_score;
+    }
+  }
+  score
+}
@@ -1,4 +1,54 @@
-use crate::board::{Piece, PlayerPiece, COLUMNS, ROWS};
+use crate::position::{Piece, PlayerPiece, Position, COLUMNS, ROWS};
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum FenError {
+  MissingField(&'static str),
+  InvalidRankCount(usize),
+  InvalidRankLength(usize),
+  InvalidPiece(char),
+  InvalidSideToMove(String),
+  InvalidCastling(String),
+  InvalidEnPassant(String),
+  InvalidHalfmoveClock(String),
+  InvalidFullmoveNumber(String),
+}
+
+impl fmt::Display for FenError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FenError::MissingField(field) => write!(f, "missing {} field", field),
+      FenError::InvalidRankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+      FenError::InvalidRankLength(rank) => write!(f, "rank {} does not have 8 squares", rank),
+      FenError::InvalidPiece(character) => write!(f, "invalid piece '{}'", character),
+      FenError::InvalidSideToMove(value) => write!(f, "invalid side to move '{}'", value),
+      FenError::InvalidCastling(value) => write!(f, "invalid castling rights '{}'", value),
+      FenError::InvalidEnPassant(value) => write!(f, "invalid en passant square '{}'", value),
+      FenError::InvalidHalfmoveClock(value) => write!(f, "invalid halfmove clock '{}'", value),
+      FenError::InvalidFullmoveNumber(value) => write!(f, "invalid fullmove number '{}'", value),
+    }
+  }
+}
+
+impl std::error::Error for FenError {}
+
+// kingside and queenside, in the same order as the checkboxes
+pub type CastlingRights = (bool, bool);
+
+// the files of the rooks castling uses, kingside and queenside
+pub type CastlingFiles = (usize, usize);
+
+pub const CLASSICAL_CASTLING_FILES: CastlingFiles = (ROWS - 1, 0);
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum CastlingNotation {
+  // `KQkq`, Chess960 rights for a rook that is not the outermost one on
+  // its side use the file of the rook instead
+  #[default]
+  XFen,
+  // the file of the rook for every right, like `HAha`
+  Shredder,
+}
 
 fn get_character_for_piece(piece: Piece) -> char {
   match piece {
@@ -12,34 +62,159 @@ fn get_character_for_piece(piece: Piece) -> char {
   }
 }
 
-fn get_castling_data_as_string(white: (bool, bool), black: (bool, bool)) -> String {
-  let mut data = String::new();
-  if white.0 {
-    data += "K";
+pub fn get_piece_for_character(character: char) -> Option<Piece> {
+  match character.to_ascii_lowercase() {
+    'r' => Some(Piece::Rook),
+    'b' => Some(Piece::Bishop),
+    'n' => Some(Piece::Knight),
+    'q' => Some(Piece::Queen),
+    'k' => Some(Piece::King),
+    'p' => Some(Piece::Pawn),
+    _ => None,
   }
-  if white.1 {
-    data += "Q";
-  }
-  if black.0 {
-    data += "k";
+}
+
+fn get_castling_data_as_string(position: &Position, notation: CastlingNotation) -> String {
+  let mut data = String::new();
+  for player in [1, 0] {
+    let rights = if player == 1 {
+      position.castle_white
+    } else {
+      position.castle_black
+    };
+    let files = position.castling_files[player];
+    let possible = get_possible_castling(&position.board, player, files, position.chess960);
+    let outer = get_outer_rooks(&position.board, player);
+    let king = get_king_file(&position.board, player);
+    for (allowed, file, outer, letter, corner) in [
+      (
+        rights.0 && possible.0,
+        files.0,
+        outer.0,
+        'K',
+        CLASSICAL_CASTLING_FILES.0,
+      ),
+      (
+        rights.1 && possible.1,
+        files.1,
+        outer.1,
+        'Q',
+        CLASSICAL_CASTLING_FILES.1,
+      ),
+    ] {
+      if !allowed {
+        continue;
+      }
+      // with the king on the e file the letters only mean the corners
+      let is_letter = outer == Some(file) && (file == corner || king != Some(4));
+      let character = match notation {
+        CastlingNotation::XFen if is_letter => letter,
+        _ => (b'A' + file as u8) as char,
+      };
+      data.push(if player == 1 {
+        character
+      } else {
+        character.to_ascii_lowercase()
+      });
+    }
   }
-  if black.1 {
-    data += "q";
+  if data.is_empty() {
+    data += "-";
   }
   data
 }
 
-pub fn generate_fen_from_board(
-  board: [[PlayerPiece; 8]; 8],
+fn is_piece(
+  board: &[[PlayerPiece; 8]; 8],
+  square: (usize, usize),
+  player: usize,
+  piece: Piece,
+) -> bool {
+  let curr = board[square.0][square.1];
+  curr.player == player && curr.piece_idx == piece
+}
+
+// the file of the player's king on its first row
+pub fn get_king_file(board: &[[PlayerPiece; 8]; 8], player: usize) -> Option<usize> {
+  let row = if player == 1 { COLUMNS - 1 } else { 0 };
+  (0..ROWS).find(|&j| is_piece(board, (row, j), player, Piece::King))
+}
+
+// the outermost rooks on the first row on the kingside and the queenside
+// of the king, the rooks `K` and `Q` stand for in X-FEN
+pub fn get_outer_rooks(
+  board: &[[PlayerPiece; 8]; 8],
+  player: usize,
+) -> (Option<usize>, Option<usize>) {
+  let row = if player == 1 { COLUMNS - 1 } else { 0 };
+  let Some(king) = get_king_file(board, player) else {
+    return (None, None);
+  };
+  let is_rook = |j: &usize| is_piece(board, (row, *j), player, Piece::Rook);
+  (
+    (king + 1..ROWS).rev().find(is_rook),
+    (0..king).find(is_rook),
+  )
+}
+
+// castling needs the king and the rook on their starting squares, the
+// king has to be on the e file unless the position is Chess960
+pub fn get_possible_castling(
+  board: &[[PlayerPiece; 8]; 8],
   player: usize,
-  castle_white: (bool, bool),
-  castle_black: (bool, bool),
-) -> String {
+  files: CastlingFiles,
+  chess960: bool,
+) -> CastlingRights {
+  let row = if player == 1 { COLUMNS - 1 } else { 0 };
+  let king = if chess960 {
+    get_king_file(board, player)
+  } else {
+    Some(4).filter(|&j| is_piece(board, (row, j), player, Piece::King))
+  };
+  match king {
+    Some(king) => (
+      files.0 > king && is_piece(board, (row, files.0), player, Piece::Rook),
+      files.1 < king && is_piece(board, (row, files.1), player, Piece::Rook),
+    ),
+    None => (false, false),
+  }
+}
+
+pub fn get_square_name(square: (usize, usize)) -> String {
+  let (i, j) = square;
+  format!("{}{}", (b'a' + j as u8) as char, COLUMNS - i)
+}
+
+// the squares a pawn of the side not to move could have skipped with a
+// double push, which needs the pawn in front of the square and both the
+// square and the pawn's starting square to be empty
+pub fn get_en_passant_squares(board: &[[PlayerPiece; 8]; 8], player: usize) -> Vec<(usize, usize)> {
+  let (target, pawn, start) = if player == 1 { (2, 3, 1) } else { (5, 4, 6) };
+  (0..ROWS)
+    .filter(|&j| {
+      let piece = board[pawn][j];
+      matches!(piece.piece_idx, Piece::Pawn)
+        && piece.player != player
+        && matches!(board[target][j].piece_idx, Piece::None)
+        && matches!(board[start][j].piece_idx, Piece::None)
+    })
+    .map(|j| (target, j))
+    .collect()
+}
+
+pub fn is_valid_en_passant(
+  board: &[[PlayerPiece; 8]; 8],
+  player: usize,
+  square: (usize, usize),
+) -> bool {
+  get_en_passant_squares(board, player).contains(&square)
+}
+
+pub fn generate_fen_from_board(position: &Position, notation: CastlingNotation) -> String {
   let mut fen_string = String::new();
   let mut empty_block_count = 0;
-  for i in 0..COLUMNS {
-    for j in 0..ROWS {
-      let curr = board[i][j];
+  for (i, row) in position.board.iter().enumerate() {
+    for curr in row.iter() {
       match curr.piece_idx {
         Piece::None => empty_block_count += 1,
         _ => {
@@ -62,8 +237,174 @@ pub fn generate_fen_from_board(
     }
     fen_string.push(if i < COLUMNS - 1 { '/' } else { ' ' });
   }
-  fen_string.push_str(if player == 0 { "b " } else { "w " });
-  fen_string += get_castling_data_as_string(castle_white, castle_black).as_str();
-  fen_string += " - 0 1";
+  fen_string.push_str(if position.player == 0 { "b " } else { "w " });
+  fen_string += get_castling_data_as_string(position, notation).as_str();
+  fen_string.push(' ');
+  match position.en_passant {
+    Some(square) if is_valid_en_passant(&position.board, position.player, square) => {
+      fen_string += get_square_name(square).as_str()
+    }
+    _ => fen_string.push('-'),
+  }
+  fen_string += format!(" {} {}", position.halfmove_clock, position.fullmove_number).as_str();
   fen_string
 }
+
+fn parse_board(placement: &str) -> Result<[[PlayerPiece; ROWS]; COLUMNS], FenError> {
+  let ranks: Vec<&str> = placement.split('/').collect();
+  if ranks.len() != COLUMNS {
+    return Err(FenError::InvalidRankCount(ranks.len()));
+  }
+  let mut board: [[PlayerPiece; ROWS]; COLUMNS] = Default::default();
+  for (i, rank) in ranks.iter().enumerate() {
+    let mut j = 0;
+    for character in rank.chars() {
+      if let Some(empty) = character.to_digit(10) {
+        if empty == 0 || empty > 8 {
+          return Err(FenError::InvalidPiece(character));
+        }
+        j += empty as usize;
+      } else {
+        let piece = get_piece_for_character(character).ok_or(FenError::InvalidPiece(character))?;
+        if j >= ROWS {
+          return Err(FenError::InvalidRankLength(COLUMNS - i));
+        }
+        board[i][j] = PlayerPiece {
+          player: character.is_ascii_uppercase() as usize,
+          piece_idx: piece,
+        };
+        j += 1;
+      }
+      if j > ROWS {
+        return Err(FenError::InvalidRankLength(COLUMNS - i));
+      }
+    }
+    if j != ROWS {
+      return Err(FenError::InvalidRankLength(COLUMNS - i));
+    }
+  }
+  Ok(board)
+}
+
+// reads `KQkq` as well as the rook files X-FEN and Shredder-FEN use for
+// Chess960, `K` and `Q` mean the corner rook when it is there and the
+// outermost rook on that side otherwise, unless the king is on the e
+// file like in a classical position, then a right without its corner
+// rook is dropped, the rights and the rook files come back indexed by
+// player
+fn parse_castling(
+  castling: &str,
+  board: &[[PlayerPiece; 8]; 8],
+) -> Result<([CastlingRights; 2], [CastlingFiles; 2]), FenError> {
+  let mut rights = [(false, false); 2];
+  let mut files = [CLASSICAL_CASTLING_FILES; 2];
+  if castling == "-" {
+    return Ok((rights, files));
+  }
+  let error = || FenError::InvalidCastling(castling.to_string());
+  for character in castling.chars() {
+    let player = character.is_ascii_uppercase() as usize;
+    let row = if player == 1 { COLUMNS - 1 } else { 0 };
+    let is_rook = |j: usize| is_piece(board, (row, j), player, Piece::Rook);
+    let outer = get_outer_rooks(board, player);
+    let is_classical = get_king_file(board, player) == Some(4);
+    let (kingside, file) = match character.to_ascii_lowercase() {
+      'k' if is_rook(CLASSICAL_CASTLING_FILES.0) => (true, CLASSICAL_CASTLING_FILES.0),
+      'q' if is_rook(CLASSICAL_CASTLING_FILES.1) => (false, CLASSICAL_CASTLING_FILES.1),
+      'k' | 'q' if is_classical => continue,
+      'k' => (true, outer.0.unwrap_or(CLASSICAL_CASTLING_FILES.0)),
+      'q' => (false, outer.1.unwrap_or(CLASSICAL_CASTLING_FILES.1)),
+      file @ 'a'..='h' => {
+        let file = file as usize - 'a' as usize;
+        // the side of the king the rook is on tells the side it castles to
+        match get_king_file(board, player) {
+          Some(king) if king != file => (file > king, file),
+          _ => return Err(error()),
+        }
+      }
+      _ => return Err(error()),
+    };
+    let (flag, rook) = if kingside {
+      (&mut rights[player].0, &mut files[player].0)
+    } else {
+      (&mut rights[player].1, &mut files[player].1)
+    };
+    if *flag {
+      return Err(error());
+    }
+    *flag = true;
+    *rook = file;
+  }
+  Ok((rights, files))
+}
+
+// a right with the king off the e file or a rook off the corner only
+// makes sense in Chess960
+fn is_chess960_castling(
+  board: &[[PlayerPiece; 8]; 8],
+  rights: [CastlingRights; 2],
+  files: [CastlingFiles; 2],
+) -> bool {
+  (0..2).any(|player| {
+    let Some(king) = get_king_file(board, player) else {
+      return false;
+    };
+    let (rights, files) = (rights[player], files[player]);
+    (rights.0 || rights.1) && king != 4
+      || rights.0 && files.0 != CLASSICAL_CASTLING_FILES.0
+      || rights.1 && files.1 != CLASSICAL_CASTLING_FILES.1
+  })
+}
+
+fn parse_en_passant(square: &str) -> Result<Option<(usize, usize)>, FenError> {
+  if square == "-" {
+    return Ok(None);
+  }
+  let characters: Vec<char> = square.chars().collect();
+  match characters[..] {
+    [file @ 'a'..='h', rank @ ('3' | '6')] => {
+      let rank = rank.to_digit(10).unwrap() as usize;
+      Ok(Some((COLUMNS - rank, file as usize - 'a' as usize)))
+    }
+    _ => Err(FenError::InvalidEnPassant(square.to_string())),
+  }
+}
+
+pub fn parse_fen(fen: &str) -> Result<Position, FenError> {
+  let mut fields = fen.split_whitespace();
+  let board = parse_board(fields.next().ok_or(FenError::MissingField("piece placement"))?)?;
+  let player = match fields.next().ok_or(FenError::MissingField("side to move"))? {
+    "w" => 1,
+    "b" => 0,
+    value => return Err(FenError::InvalidSideToMove(value.to_string())),
+  };
+  // the remaining fields are often left out when positions are shared,
+  // so fall back to the values generate_fen_from_board would write
+  let (rights, castling_files) = parse_castling(fields.next().unwrap_or("-"), &board)?;
+  let chess960 = is_chess960_castling(&board, rights, castling_files);
+  let en_passant = parse_en_passant(fields.next().unwrap_or("-"))?;
+  let halfmove_clock = match fields.next() {
+    Some(value) => value
+      .parse()
+      .map_err(|_| FenError::InvalidHalfmoveClock(value.to_string()))?,
+    None => 0,
+  };
+  let fullmove_number = match fields.next() {
+    Some(value) => match value.parse() {
+      Ok(number) if number > 0 => number,
+      _ => return Err(FenError::InvalidFullmoveNumber(value.to_string())),
+    },
+    None => 1,
+  };
+  Ok(Position {
+    board,
+    player,
+    castle_white: rights[1],
+    castle_black: rights[0],
+    castling_files,
+    chess960,
+    en_passant,
+    halfmove_clock,
+    fullmove_number,
+  })
+}
@@ -0,0 +1,241 @@
+use crate::{
+  position::{Piece, Position, COLUMNS, ROWS},
+  random::Random,
+};
+use std::fmt;
+
+// attempts before giving up on material that cannot be placed legally
+const MAX_ATTEMPTS: usize = 10_000;
+const MAX_PIECES: usize = 15;
+const MAX_PAWNS: usize = 8;
+const MAX_RANDOM_PIECES: usize = 30;
+const RANDOM_PIECES: [Piece; 5] = [
+  Piece::Queen,
+  Piece::Rook,
+  Piece::Bishop,
+  Piece::Knight,
+  Piece::Pawn,
+];
+
+#[derive(Debug, PartialEq)]
+pub enum MaterialError {
+  Empty,
+  UnknownWord(String),
+  SameColour,
+  TooManyKings,
+  TooManyPieces(usize),
+  TooManyPawns(usize),
+}
+
+impl fmt::Display for MaterialError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MaterialError::Empty => write!(f, "no material given"),
+      MaterialError::UnknownWord(word) => write!(f, "unknown word '{}'", word),
+      MaterialError::SameColour => write!(f, "both sides have the same colour"),
+      MaterialError::TooManyKings => write!(f, "a side has more than one king"),
+      MaterialError::TooManyPieces(count) => write!(f, "too many pieces ({})", count),
+      MaterialError::TooManyPawns(count) => write!(f, "too many pawns ({})", count),
+    }
+  }
+}
+
+impl std::error::Error for MaterialError {}
+
+// the pieces to place besides the two kings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Material {
+  Pieces {
+    white: Vec<Piece>,
+    black: Vec<Piece>,
+  },
+  // this many pieces of random kinds and colours
+  Random(usize),
+}
+
+fn get_piece_for_letter(letter: char) -> Option<Piece> {
+  match letter {
+    'K' => Some(Piece::King),
+    'Q' => Some(Piece::Queen),
+    'R' => Some(Piece::Rook),
+    'B' => Some(Piece::Bishop),
+    'N' => Some(Piece::Knight),
+    'P' => Some(Piece::Pawn),
+    _ => None,
+  }
+}
+
+fn get_piece_for_word(word: &str) -> Option<Piece> {
+  match word.strip_suffix('s').unwrap_or(word) {
+    "king" => Some(Piece::King),
+    "queen" => Some(Piece::Queen),
+    "rook" => Some(Piece::Rook),
+    "bishop" => Some(Piece::Bishop),
+    "knight" => Some(Piece::Knight),
+    "pawn" => Some(Piece::Pawn),
+    _ => None,
+  }
+}
+
+fn get_number_for_word(word: &str) -> Option<usize> {
+  let number = match word {
+    "a" | "an" | "one" => 1,
+    "two" => 2,
+    "three" => 3,
+    "four" => 4,
+    "five" => 5,
+    "six" => 6,
+    "seven" => 7,
+    "eight" => 8,
+    _ => return word.parse().ok(),
+  };
+  Some(number)
+}
+
+// one side, either in letters like `KRP` or in words like `white queen
+// and two rooks`, the king is always there and may be left out
+fn parse_side(text: &[&str]) -> Result<(Option<usize>, Vec<Piece>), MaterialError> {
+  if let [letters] = text {
+    let pieces: Option<Vec<Piece>> = letters.chars().map(get_piece_for_letter).collect();
+    if let Some(pieces) = pieces {
+      let kings = pieces.iter().filter(|&&piece| piece == Piece::King).count();
+      if kings > 1 {
+        return Err(MaterialError::TooManyKings);
+      }
+      let pieces = pieces.into_iter().filter(|&piece| piece != Piece::King);
+      return Ok((None, pieces.collect()));
+    }
+  }
+  let (mut colour, mut pieces, mut count) = (None, Vec::new(), 1);
+  for word in text.iter().map(|word| word.to_lowercase()) {
+    if let Some(number) = get_number_for_word(word.as_str()) {
+      count = number;
+      continue;
+    }
+    match (word.as_str(), get_piece_for_word(word.as_str())) {
+      ("white", _) => colour = Some(1),
+      ("black", _) => colour = Some(0),
+      ("and", _) => {}
+      (_, Some(Piece::King)) if count == 1 => {}
+      (_, Some(Piece::King)) => return Err(MaterialError::TooManyKings),
+      (_, Some(piece)) if piece != Piece::King => {
+        pieces.extend((0..count).map(|_| piece));
+        count = 1;
+      }
+      _ => return Err(MaterialError::UnknownWord(word)),
+    }
+  }
+  Ok((colour, pieces))
+}
+
+// reads specifications like `KRP vs KR`, `white queen vs two rooks` or
+// `5 random pieces`, a single side plays against a bare king
+pub fn parse_material(spec: &str) -> Result<Material, MaterialError> {
+  let words: Vec<&str> = spec
+    .split(|character: char| character.is_whitespace() || character == ',')
+    .filter(|word| !word.is_empty())
+    .collect();
+  if words.is_empty() {
+    return Err(MaterialError::Empty);
+  }
+  let lowercase: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+  if let [count, random, rest @ ..] = &lowercase[..] {
+    if random == "random" && rest.iter().all(|word| word == "piece" || word == "pieces") {
+      let count =
+        get_number_for_word(count).ok_or_else(|| MaterialError::UnknownWord(count.to_string()))?;
+      if count > MAX_RANDOM_PIECES {
+        return Err(MaterialError::TooManyPieces(count));
+      }
+      return Ok(Material::Random(count));
+    }
+  }
+
+  let sides: Vec<&[&str]> = words
+    .split(|word| matches!(word.to_lowercase().as_str(), "vs" | "v" | "versus"))
+    .collect();
+  let (first, second) = match sides[..] {
+    [first] => (parse_side(first)?, (None, Vec::new())),
+    [first, second] => (parse_side(first)?, parse_side(second)?),
+    _ => return Err(MaterialError::UnknownWord("vs".to_string())),
+  };
+  // the first side is white unless either says otherwise
+  let first_colour = match (first.0, second.0) {
+    (Some(first), Some(second)) if first == second => return Err(MaterialError::SameColour),
+    (Some(first), _) => first,
+    (None, Some(second)) => 1 - second,
+    (None, None) => 1,
+  };
+  let (white, black) = if first_colour == 1 {
+    (first.1, second.1)
+  } else {
+    (second.1, first.1)
+  };
+  for pieces in [&white, &black] {
+    if pieces.len() > MAX_PIECES {
+      return Err(MaterialError::TooManyPieces(pieces.len()));
+    }
+    let pawns = pieces.iter().filter(|&&piece| piece == Piece::Pawn).count();
+    if pawns > MAX_PAWNS {
+      return Err(MaterialError::TooManyPawns(pawns));
+    }
+  }
+  Ok(Material::Pieces { white, black })
+}
+
+// puts the kings and the pieces on random empty squares, pawns stay off
+// the back rows, none when the board is full
+fn place_pieces(
+  random: &mut Random,
+  white: &[Piece],
+  black: &[Piece],
+  player: usize,
+) -> Option<Position> {
+  let mut position = Position::new();
+  position.player = player;
+  let pieces = [(1, Piece::King), (0, Piece::King)]
+    .into_iter()
+    .chain(white.iter().map(|&piece| (1, piece)))
+    .chain(black.iter().map(|&piece| (0, piece)));
+  for (owner, piece) in pieces {
+    let rows = if piece == Piece::Pawn {
+      1..ROWS - 1
+    } else {
+      0..ROWS
+    };
+    let free: Vec<(usize, usize)> = rows
+      .flat_map(|i| (0..COLUMNS).map(move |j| (i, j)))
+      .filter(|&square| position.get_piece(square).is_none())
+      .collect();
+    if free.is_empty() {
+      return None;
+    }
+    position.put_piece(free[random.get_below(free.len())], owner, piece);
+  }
+  Some(position)
+}
+
+// a random position with the material that passes validation, so each
+// side has one king, no pawn is on a back row and the side not to move
+// is not in check, none when no try found one
+pub fn generate_position(material: &Material, player: usize) -> Option<Position> {
+  let mut random = Random::new();
+  (0..MAX_ATTEMPTS).find_map(|_| {
+    let (white, black) = match material {
+      Material::Pieces { white, black } => (white.clone(), black.clone()),
+      Material::Random(count) => {
+        let (mut white, mut black) = (Vec::new(), Vec::new());
+        for _ in 0..*count {
+          let piece = RANDOM_PIECES[random.get_below(RANDOM_PIECES.len())];
+          if random.get_below(2) == 1 {
+            white.push(piece);
+          } else {
+            black.push(piece);
+          }
+        }
+        (white, black)
+      }
+    };
+    place_pieces(&mut random, &white, &black, player)
+      .filter(|position| position.validate().is_empty())
+  })
+}
@@ -0,0 +1,61 @@
+use crate::{annotation::Annotations, position::Position};
+
+// older edits are dropped once the history grows past this
+pub const MAX_ENTRIES: usize = 500;
+
+// a position with the arrows and highlights drawn on it
+pub type Snapshot = (Position, Annotations);
+
+// snapshots of the whole position and its annotations, an edit is
+// recorded whenever either differs from the last snapshot that was seen
+pub struct History {
+  current: Snapshot,
+  past: Vec<Snapshot>,
+  future: Vec<Snapshot>,
+}
+
+impl History {
+  pub fn new(position: &Position, annotations: &Annotations) -> History {
+    History {
+      current: (position.clone(), annotations.clone()),
+      past: Vec::new(),
+      future: Vec::new(),
+    }
+  }
+
+  pub fn record(&mut self, position: &Position, annotations: &Annotations) -> bool {
+    if self.current.0 == *position && self.current.1 == *annotations {
+      return false;
+    }
+    let snapshot = (position.clone(), annotations.clone());
+    let previous = std::mem::replace(&mut self.current, snapshot);
+    self.past.push(previous);
+    if self.past.len() > MAX_ENTRIES {
+      self.past.remove(0);
+    }
+    self.future.clear();
+    true
+  }
+
+  pub fn undo(&mut self) -> Option<Snapshot> {
+    let previous = self.past.pop()?;
+    let current = std::mem::replace(&mut self.current, previous);
+    self.future.push(current);
+    Some(self.current.clone())
+  }
+
+  pub fn redo(&mut self) -> Option<Snapshot> {
+    let next = self.future.pop()?;
+    let current = std::mem::replace(&mut self.current, next);
+    self.past.push(current);
+    Some(self.current.clone())
+  }
+
+  pub fn can_undo(&self) -> bool {
+    !self.past.is_empty()
+  }
+
+  pub fn can_redo(&self) -> bool {
+    !self.future.is_empty()
+  }
+}
@@ -0,0 +1,179 @@
+use crate::Rect;
+use allegro::{Color, Core, Event, KeyCode};
+use allegro_font::{Font, FontAlign, FontDrawing};
+use allegro_primitives::PrimitivesAddon;
+
+const PADDING_X: f32 = 10.0;
+const MARGIN: f32 = 6.0;
+const MAX_DIGITS: usize = 5;
+
+pub struct TextInput<'a> {
+  label: &'a str,
+  rect: Rect,
+  text: String,
+  digits_only: bool,
+  max_length: usize,
+  is_focused: bool,
+  is_hovering: bool,
+  padding_y: f32,
+  label_y: f32,
+}
+
+impl<'a> TextInput<'a> {
+  // the label is drawn above the box, so the input takes up a line of
+  // text more than `rect`
+  pub fn new(rect: Rect, label: &'a str, text: &str, font: &Font) -> TextInput<'a> {
+    let line_height = font.get_line_height() as f32;
+    let char_width = font.get_text_width("W").max(1) as f32;
+    TextInput {
+      label,
+      label_y: rect.y,
+      rect: Rect::new(
+        rect.x,
+        rect.y + line_height + MARGIN,
+        rect.width,
+        rect.height,
+      ),
+      text: text.to_string(),
+      digits_only: false,
+      max_length: ((rect.width - PADDING_X * 2.0) / char_width) as usize,
+      is_focused: false,
+      is_hovering: false,
+      padding_y: (rect.height - line_height) / 2.0,
+    }
+  }
+
+  pub fn new_numeric(rect: Rect, label: &'a str, value: u32, font: &Font) -> TextInput<'a> {
+    let mut input = TextInput::new(rect, label, value.to_string().as_str(), font);
+    input.digits_only = true;
+    input.max_length = input.max_length.min(MAX_DIGITS);
+    input
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    core.draw_text(
+      font,
+      Color::from_rgb(154, 153, 153),
+      self.rect.x,
+      self.label_y,
+      FontAlign::Left,
+      self.label,
+    );
+    primitives.draw_filled_rounded_rectangle(
+      self.rect.x,
+      self.rect.y,
+      self.rect.x + self.rect.width,
+      self.rect.y + self.rect.height,
+      5.0,
+      5.0,
+      Color::from_rgb(36, 34, 40),
+    );
+    if self.is_focused || self.is_hovering {
+      primitives.draw_rounded_rectangle(
+        self.rect.x,
+        self.rect.y,
+        self.rect.x + self.rect.width,
+        self.rect.y + self.rect.height,
+        5.0,
+        5.0,
+        if self.is_focused {
+          Color::from_rgb(67, 111, 157)
+        } else {
+          Color::from_rgb(82, 82, 82)
+        },
+        2.0,
+      );
+    }
+    let text_x = self.rect.x + PADDING_X;
+    let text_y = self.rect.y + self.padding_y;
+    core.draw_text(
+      font,
+      Color::from_rgb(255, 255, 255),
+      text_x,
+      text_y,
+      FontAlign::Left,
+      self.text.as_str(),
+    );
+    if self.is_focused {
+      let caret_x = text_x + font.get_text_width(self.text.as_str()) as f32 + 1.0;
+      primitives.draw_line(
+        caret_x,
+        text_y,
+        caret_x,
+        text_y + font.get_line_height() as f32,
+        Color::from_rgb(177, 177, 177),
+        1.0,
+      );
+    }
+  }
+
+  pub fn event_listener(&mut self, event: &Event) -> bool {
+    match event {
+      Event::MouseAxes { x, y, .. } => {
+        self.is_hovering = self.rect.contains_point(*x as f32, *y as f32);
+      }
+      Event::MouseButtonDown { x, y, .. } => {
+        self.is_focused = self.rect.contains_point(*x as f32, *y as f32);
+        return self.is_focused;
+      }
+      Event::KeyChar {
+        keycode, unichar, ..
+      } if self.is_focused => {
+        match keycode {
+          KeyCode::Backspace => {
+            self.text.pop();
+          }
+          KeyCode::Enter | KeyCode::Escape | KeyCode::Tab => {
+            self.is_focused = false;
+          }
+          _ => {
+            let accepted = if self.digits_only {
+              unichar.is_ascii_digit()
+            } else {
+              !unichar.is_control()
+            };
+            if accepted && self.text.chars().count() < self.max_length {
+              self.text.push(*unichar);
+            }
+          }
+        }
+        return true;
+      }
+      _ => {}
+    }
+    false
+  }
+
+  pub fn is_focused(&self) -> bool {
+    self.is_focused
+  }
+
+  pub fn set_focused(&mut self, is_focused: bool) {
+    self.is_focused = is_focused;
+  }
+
+  pub fn get_text(&self) -> &str {
+    self.text.as_str()
+  }
+
+  pub fn set_text(&mut self, text: &str) {
+    self.text = text.chars().take(self.max_length).collect();
+  }
+
+  pub fn get_number(&self) -> u32 {
+    self.text.parse().unwrap_or(0)
+  }
+
+  // values too long for the box show as the largest one that fits, so
+  // the text never reads as a different number
+  pub fn set_number(&mut self, value: u32) {
+    let largest = 10u32
+      .checked_pow(self.max_length as u32)
+      .map_or(u32::MAX, |power| power - 1);
+    self.set_text(value.min(largest).to_string().as_str());
+  }
+
+  pub fn get_next_y(&self) -> f32 {
+    self.rect.y + self.rect.height
+  }
+}
@@ -1,8 +1,35 @@
+pub mod annotation;
+pub mod attack;
+#[cfg(feature = "gui")]
 pub mod board;
+#[cfg(feature = "gui")]
 pub mod button;
+#[cfg(feature = "gui")]
 pub mod checkbox;
+pub mod chess960;
+#[cfg(feature = "gui")]
+pub mod dialog;
+#[cfg(feature = "gui")]
 pub mod dropdown;
+pub mod engine;
+pub mod eval;
 pub mod fen;
+pub mod generator;
+pub mod history;
+#[cfg(feature = "gui")]
+pub mod input;
+pub mod movegen;
+#[cfg(feature = "gui")]
+pub mod movelist;
+#[cfg(feature = "gui")]
+pub mod panel;
+pub mod pgn;
+pub mod position;
+pub mod random;
+pub mod san;
+pub mod status;
+pub mod transform;
+pub mod validate;
 
 #[derive(Clone)]
 pub struct Rect {
@@ -1,18 +1,43 @@
-use allegro::{Bitmap, Color, Core, Display, Event, EventQueue, Flag, Timer};
-use allegro_font::FontAddon;
+use allegro::{Bitmap, Color, Core, Display, Event, EventQueue, Flag, KeyCode, Timer, CTRL};
+use allegro_font::{FontAddon, FontAlign, FontDrawing};
 use allegro_image::ImageAddon;
 use allegro_primitives::PrimitivesAddon;
 use allegro_ttf::{TtfAddon, TtfFlags};
 use board_editor::{
-  board::Board, button::Button, checkbox::CheckBoxGroup, dropdown::Dropdown, Rect,
+  board::Board,
+  button::Button,
+  checkbox::CheckBox,
+  chess960::{get_random_number, POSITION_COUNT},
+  dialog::GeneratorDialog,
+  engine::Engine,
+  fen::CastlingNotation,
+  history::History,
+  input::TextInput,
+  movelist::MoveList,
+  panel::{EnginePanel, PgnPanel, SidePanel},
+  pgn::{parse_pgn, Game},
+  position::Position,
+  san::parse_move_line,
+  status::Status,
+  validate::Problem,
+  Rect,
 };
-use std::path::PathBuf;
+use std::{error::Error, fs, path::PathBuf};
 
 const DISPLAY_HEIGHT: i32 = 950;
-const DISPLAY_WIDTH: i32 = 1050;
+const DISPLAY_WIDTH: i32 = 1660;
 
 const INP_WIDTH: f32 = 270.0;
 const INP_HEIGHT: f32 = 40.0;
+// the Ctrl shortcuts of the transform buttons, in their order
+const TRANSFORM_KEYS: [KeyCode; 6] = [
+  KeyCode::M,
+  KeyCode::R,
+  KeyCode::Left,
+  KeyCode::Right,
+  KeyCode::Up,
+  KeyCode::Down,
+];
 
 fn position_window_at_centre(core: &Core, display: &Display) {
   let monitor_info = core.get_monitor_info(0).unwrap();
@@ -25,6 +50,58 @@ fn position_window_at_centre(core: &Core, display: &Display) {
   display.set_window_position(x, y);
 }
 
+// the side to move and whether it is in check, mated or stalemated, only
+// worth computing for positions that passed validation
+fn get_game_status(position: &Position) -> Vec<String> {
+  let side = if position.player == 1 {
+    "White"
+  } else {
+    "Black"
+  };
+  let mut lines = vec![match position.get_status() {
+    Status::Normal => format!("{} to move", side),
+    Status::Check => format!("{} is in check", side),
+    Status::Checkmate => format!("{} is checkmated", side),
+    Status::Stalemate => format!("{} is stalemated", side),
+  }];
+  if position.is_insufficient_material() {
+    lines.push("Insufficient material".to_string());
+  }
+  lines
+}
+
+// mirrors, swaps or shifts the board for one of `TRANSFORM_KEYS`, with a
+// message when the pieces cannot be shifted
+fn transform_board(board: &mut Board, keycode: KeyCode) -> Option<(&'static str, String)> {
+  let (rows, columns) = match keycode {
+    KeyCode::M => {
+      board.mirror_files();
+      return None;
+    }
+    KeyCode::R => {
+      board.swap_colours();
+      return None;
+    }
+    KeyCode::Left => (0, -1),
+    KeyCode::Right => (0, 1),
+    KeyCode::Up => (-1, 0),
+    _ => (1, 0),
+  };
+  if board.shift_pieces(rows, columns) {
+    None
+  } else {
+    Some((
+      "Could not shift the pieces,",
+      "one would leave the board".to_string(),
+    ))
+  }
+}
+
+fn load_game(path: &str) -> Result<Game, Box<dyn Error>> {
+  let pgn = fs::read_to_string(path)?;
+  Ok(parse_pgn(pgn.as_str())?)
+}
+
 fn main() {
   let core = Core::init().unwrap();
   let _image_addon = ImageAddon::init(&core).unwrap();
@@ -88,34 +165,13 @@ fn main() {
 
   let dropdown_x = dropdown_rect.x;
   let dropdown_y = dropdown_rect.y;
-  let dropdown_height = dropdown_rect.height;
 
-  let mut dropdown = Dropdown::new(
-    dropdown_rect,
-    vec!["Black to play", "White to play"],
-    0,
-    &font,
-  );
-
-  let mut check1 = CheckBoxGroup::new(
-    "White",
-    dropdown_x + 20.0,
-    dropdown_y + dropdown_height + 150.0,
-    20.0,
-    vec!["O-O", "O-O-O"],
-    &font,
-  );
-  let mut check2 = CheckBoxGroup::new(
-    "Black ",
-    dropdown_x + 20.0,
-    check1.get_next_y(),
-    20.0,
-    vec!["O-O", "O-O-O"],
-    &font,
-  );
+  let mut side_panel = SidePanel::new(dropdown_rect, &font);
+  side_panel.update(board.get_position_mut());
 
-  let y = check2.get_next_y();
-  let mut buttons: [Button; 4] = [
+  let y = side_panel.get_next_y();
+  let half_width = (INP_WIDTH - 10.0) / 2.0;
+  let mut buttons: [Button; 7] = [
     Button::new(
       Rect::new(dropdown_x, y, INP_WIDTH, INP_HEIGHT),
       "STARTING POSITION",
@@ -146,7 +202,207 @@ fn main() {
       "COPY FEN TO CLIPBOARD",
       &font,
     ),
+    Button::new(
+      Rect::new(
+        dropdown_x,
+        y + (INP_HEIGHT * 4.0) + 40.0,
+        INP_WIDTH,
+        INP_HEIGHT,
+      ),
+      "PASTE FEN FROM CLIPBOARD",
+      &font,
+    ),
+    Button::new(
+      Rect::new(
+        dropdown_x,
+        y + (INP_HEIGHT * 5.0) + 50.0,
+        half_width,
+        INP_HEIGHT,
+      ),
+      "UNDO",
+      &font,
+    ),
+    Button::new(
+      Rect::new(
+        dropdown_x + half_width + 10.0,
+        y + (INP_HEIGHT * 5.0) + 50.0,
+        half_width,
+        INP_HEIGHT,
+      ),
+      "REDO",
+      &font,
+    ),
+  ];
+  let mut analysis_mode = CheckBox::new(
+    dropdown_x + 20.0,
+    y + (INP_HEIGHT * 6.0) + 70.0,
+    20.0,
+    "Analysis mode",
+  );
+  let mut chess960 = CheckBox::new(
+    dropdown_x + half_width + 30.0,
+    y + (INP_HEIGHT * 6.0) + 70.0,
+    20.0,
+    "Chess960",
+  );
+  // the position transforms sit in a row over the board, each button as
+  // wide as its text
+  let toolbar_rect = board.get_toolbar_rect(INP_HEIGHT);
+  let mut toolbar_x = toolbar_rect.x;
+  let mut transform_buttons = [
+    "MIRROR FILES",
+    "SWAP COLOURS",
+    "SHIFT LEFT",
+    "SHIFT RIGHT",
+    "SHIFT UP",
+    "SHIFT DOWN",
+  ]
+  .map(|text| {
+    let width = font.get_text_width(text) as f32 + 40.0;
+    let rect = Rect::new(toolbar_x, toolbar_rect.y, width, INP_HEIGHT);
+    toolbar_x += width + 10.0;
+    Button::new(rect, text, &font)
+  });
+  let game_status_y = analysis_mode.get_next_y() + 25.0;
+  let mut game_status: Vec<String> = Vec::new();
+  let message_rect = board.get_footer_rect(INP_HEIGHT);
+  let mut message: Option<(&str, String)> = None;
+
+  let status_x = dropdown_x + INP_WIDTH + 40.0;
+  let mut problems: Vec<Problem> = Vec::new();
+  // copying an illegal position needs a second click on the same
+  // position, this is the position the first click was on
+  let mut confirm_copy: Option<Position> = None;
+
+  let mut history = History::new(board.get_position(), board.get_annotations());
+
+  let mut pgn_panel = PgnPanel::new(
+    Rect::new(status_x, dropdown_y + 280.0, INP_WIDTH, INP_HEIGHT),
+    &font,
+  );
+  let pgn_y = pgn_panel.get_next_y();
+  let mut pgn_buttons: [Button; 3] = [
+    Button::new(
+      Rect::new(status_x, pgn_y, INP_WIDTH, INP_HEIGHT),
+      "COPY PGN TO CLIPBOARD",
+      &font,
+    ),
+    Button::new(
+      Rect::new(status_x, pgn_y + INP_HEIGHT + 10.0, INP_WIDTH, INP_HEIGHT),
+      "SAVE PGN TO FILE",
+      &font,
+    ),
+    Button::new(
+      Rect::new(
+        status_x,
+        pgn_y + (INP_HEIGHT * 2.0) + 20.0,
+        INP_WIDTH,
+        INP_HEIGHT,
+      ),
+      "LOAD PGN FROM FILE",
+      &font,
+    ),
+  ];
+
+  let engine_x = status_x + INP_WIDTH + 40.0;
+  let mut engine_panel = EnginePanel::new(
+    Rect::new(engine_x, dropdown_y, INP_WIDTH, INP_HEIGHT),
+    &font,
+  );
+  let engine_buttons_y = engine_panel.get_buttons_y();
+  let mut engine_buttons: [Button; 2] = [
+    Button::new(
+      Rect::new(engine_x, engine_buttons_y, half_width, INP_HEIGHT),
+      "START ENGINE",
+      &font,
+    ),
+    Button::new(
+      Rect::new(
+        engine_x + half_width + 10.0,
+        engine_buttons_y,
+        half_width,
+        INP_HEIGHT,
+      ),
+      "STOP ENGINE",
+      &font,
+    ),
   ];
+  let mut engine: Option<Engine> = None;
+  // the position the engine was last asked about, none while the board
+  // holds an illegal position
+  let mut analysed_fen: Option<String> = None;
+
+  // a line of moves drawn as arrows, wide enough for a few moves
+  let move_line_y = DISPLAY_HEIGHT as f32 - INP_HEIGHT - font.get_line_height() as f32 - 26.0;
+  let mut move_line = TextInput::new(
+    Rect::new(status_x, move_line_y, INP_WIDTH * 2.0 + 40.0, INP_HEIGHT),
+    "Move line to draw, like 1. e4 e5 2. Nf3",
+    "",
+    &font,
+  );
+  let mut move_line_error: Option<String> = None;
+  // the position and text the move line was last parsed from
+  let mut parsed_move_line: Option<(Position, String)> = None;
+
+  let mut chess960_number = TextInput::new_numeric(
+    Rect::new(engine_x, engine_panel.get_next_y(), half_width, INP_HEIGHT),
+    "Chess960 number",
+    518,
+    &font,
+  );
+  let chess960_y = chess960_number.get_next_y() - INP_HEIGHT;
+  let mut chess960_buttons: [Button; 2] = [
+    Button::new(
+      Rect::new(
+        engine_x + half_width + 10.0,
+        chess960_y,
+        half_width,
+        INP_HEIGHT,
+      ),
+      "960 POSITION",
+      &font,
+    ),
+    Button::new(
+      Rect::new(
+        engine_x,
+        chess960_y + INP_HEIGHT + 10.0,
+        half_width,
+        INP_HEIGHT,
+      ),
+      "RANDOM 960",
+      &font,
+    ),
+  ];
+  let mut shredder_fen = CheckBox::new(
+    engine_x + half_width + 30.0,
+    chess960_y + INP_HEIGHT + 20.0,
+    20.0,
+    "Shredder-FEN",
+  );
+
+  let mut random_position = Button::new(
+    Rect::new(
+      engine_x,
+      chess960_y + (INP_HEIGHT + 10.0) * 2.0,
+      INP_WIDTH,
+      INP_HEIGHT,
+    ),
+    "RANDOM POSITION",
+    &font,
+  );
+  let mut generator_dialog =
+    GeneratorDialog::new(Rect::new(150.0, 300.0, 500.0, 300.0), INP_HEIGHT, &font);
+
+  let move_list_y = chess960_y + (INP_HEIGHT * 3.0) + 40.0;
+  let mut move_list = MoveList::new(
+    Rect::new(
+      engine_x,
+      move_list_y,
+      INP_WIDTH,
+      move_line_y - move_list_y - 20.0,
+    ),
+    &font,
+  );
 
   let mut redraw = true;
 
@@ -155,13 +411,59 @@ fn main() {
     if redraw && queue.is_empty() {
       core.clear_to_color(Color::from_rgb(22, 21, 18));
 
-      dropdown.draw(&core, &primitives, &font);
-      for button in buttons.iter() {
+      for button in buttons
+        .iter()
+        .chain(pgn_buttons.iter())
+        .chain(engine_buttons.iter())
+        .chain(chess960_buttons.iter())
+        .chain([&random_position])
+        .chain(transform_buttons.iter())
+      {
         button.draw(&core, &primitives, &font);
       }
+      pgn_panel.draw(&core, &primitives, &font);
+      analysis_mode.draw(&core, &primitives, &font);
+      chess960.draw(&core, &primitives, &font);
+      chess960_number.draw(&core, &primitives, &font);
+      shredder_fen.draw(&core, &primitives, &font);
+      move_list.draw(&core, &primitives, &font);
+      engine_panel.draw(&core, &primitives, &font);
+      move_line.draw(&core, &primitives, &font);
+      if let Some(error) = &move_line_error {
+        core.draw_text(
+          &font,
+          Color::from_rgb(224, 108, 117),
+          engine_x + INP_WIDTH,
+          move_line_y,
+          FontAlign::Right,
+          error,
+        );
+      }
 
-      check1.draw(&core, &primitives, &font);
-      check2.draw(&core, &primitives, &font);
+      side_panel.draw(&core, &primitives, &font);
+      for (idx, line) in game_status.iter().enumerate() {
+        core.draw_text(
+          &font,
+          Color::from_rgb(154, 153, 153),
+          dropdown_x,
+          game_status_y + font.get_line_height() as f32 * idx as f32,
+          FontAlign::Left,
+          line,
+        );
+      }
+      if let Some((title, text)) = &message {
+        let color = Color::from_rgb(224, 108, 117);
+        let (message_x, message_y) = (message_rect.x, message_rect.y);
+        core.draw_text(&font, color, message_x, message_y, FontAlign::Left, title);
+        core.draw_text(
+          &font,
+          color,
+          message_x,
+          message_y + font.get_line_height() as f32,
+          FontAlign::Left,
+          text,
+        );
+      }
       board.draw(
         &core,
         &display,
@@ -169,7 +471,38 @@ fn main() {
         &white_pieces,
         &black_pieces,
         &pointer,
+        &font,
+      );
+      core.draw_text(
+        &font,
+        Color::from_rgb(154, 153, 153),
+        status_x,
+        dropdown_y,
+        FontAlign::Left,
+        "Position check",
       );
+      if problems.is_empty() {
+        core.draw_text(
+          &font,
+          Color::from_rgb(129, 182, 76),
+          status_x,
+          dropdown_y + font.get_line_height() as f32 * 1.5,
+          FontAlign::Left,
+          "No problems found",
+        );
+      }
+      for (idx, problem) in problems.iter().enumerate() {
+        core.draw_text(
+          &font,
+          Color::from_rgb(224, 108, 117),
+          status_x,
+          dropdown_y + font.get_line_height() as f32 * (1.5 + idx as f32),
+          FontAlign::Left,
+          problem.to_string().as_str(),
+        );
+      }
+      side_panel.draw_dropdowns(&core, &primitives, &font);
+      generator_dialog.draw(&core, &primitives, &font);
       core.flip_display();
       redraw = false;
     }
@@ -177,9 +510,74 @@ fn main() {
     match event {
       Event::DisplayClose { .. } => break 'running,
       Event::TimerTick { .. } => redraw = true,
+      Event::KeyChar {
+        keycode: KeyCode::V,
+        modifiers,
+        ..
+      } if modifiers & CTRL && !generator_dialog.is_open() => {
+        message = match board.paste_fen() {
+          Ok(()) => {
+            side_panel.reload(board.get_position());
+            None
+          }
+          Err(error) => Some(("Could not paste FEN:", error.to_string())),
+        };
+      }
+      Event::KeyChar {
+        keycode: KeyCode::Z,
+        modifiers,
+        ..
+      } if modifiers & CTRL && !generator_dialog.is_open() => {
+        if let Some((position, annotations)) = history.undo() {
+          board.set_position(position);
+          board.set_annotations(annotations);
+          side_panel.reload(board.get_position());
+        }
+      }
+      Event::KeyChar {
+        keycode: KeyCode::Y,
+        modifiers,
+        ..
+      } if modifiers & CTRL && !generator_dialog.is_open() => {
+        if let Some((position, annotations)) = history.redo() {
+          board.set_position(position);
+          board.set_annotations(annotations);
+          side_panel.reload(board.get_position());
+        }
+      }
+      Event::KeyChar {
+        keycode:
+          keycode @ (KeyCode::M
+          | KeyCode::R
+          | KeyCode::Left
+          | KeyCode::Right
+          | KeyCode::Up
+          | KeyCode::Down),
+        modifiers,
+        ..
+      } if modifiers & CTRL && !generator_dialog.is_open() => {
+        message = transform_board(&mut board, keycode);
+        side_panel.reload(board.get_position());
+      }
       _ => {
-        if !board.event_listener(&event) {
-          dropdown.event_listener(&event);
+        if generator_dialog.is_open() {
+          if let Some(position) = generator_dialog.event_listener(&event) {
+            board.set_position(position);
+            side_panel.reload(board.get_position());
+          }
+        } else if side_panel.is_focused() {
+          side_panel.event_listener(&event, board.get_position_mut());
+        } else if board.event_listener(&event) {
+          // keys typed after a click on the board are for the board
+          if let Event::MouseButtonDown { .. } = event {
+            side_panel.unfocus_inputs();
+            pgn_panel.unfocus_inputs();
+            engine_panel.unfocus_inputs();
+            move_line.set_focused(false);
+            chess960_number.set_focused(false);
+          }
+        } else {
+          side_panel.event_listener(&event, board.get_position_mut());
 
           for (idx, button) in buttons.iter_mut().enumerate() {
             if button.event_listener(&event) {
@@ -187,16 +585,204 @@ fn main() {
                 0 => board.set_starting_position(),
                 1 => board.clear_board(),
                 2 => board.flip_board(),
-                3 => board.generate_fen(check1.get_values(), check2.get_values()),
+                3 => {
+                  if problems.is_empty() || confirm_copy.as_ref() == Some(board.get_position()) {
+                    board.generate_fen();
+                    message = None;
+                    confirm_copy = None;
+                  } else {
+                    message = Some((
+                      "The position is illegal,",
+                      "click again to copy it anyway".to_string(),
+                    ));
+                    confirm_copy = Some(board.get_position().clone());
+                  }
+                }
+                4 => {
+                  message = match board.paste_fen() {
+                    Ok(()) => {
+                      side_panel.reload(board.get_position());
+                      None
+                    }
+                    Err(error) => Some(("Could not paste FEN:", error.to_string())),
+                  }
+                }
+                5 => {
+                  if let Some((position, annotations)) = history.undo() {
+                    board.set_position(position);
+                    board.set_annotations(annotations);
+                    side_panel.reload(board.get_position());
+                  }
+                }
+                6 => {
+                  if let Some((position, annotations)) = history.redo() {
+                    board.set_position(position);
+                    board.set_annotations(annotations);
+                    side_panel.reload(board.get_position());
+                  }
+                }
                 _ => {}
               }
             }
           }
-          if !check1.event_listener(&event) {
-            check2.event_listener(&event);
+
+          for (keycode, button) in TRANSFORM_KEYS.iter().zip(transform_buttons.iter_mut()) {
+            if button.event_listener(&event) {
+              message = transform_board(&mut board, *keycode);
+              side_panel.reload(board.get_position());
+            }
+          }
+
+          pgn_panel.event_listener(&event);
+          for (idx, button) in pgn_buttons.iter_mut().enumerate() {
+            if button.event_listener(&event) {
+              let tags = pgn_panel.get_tags();
+              message = match idx {
+                0 => board
+                  .copy_pgn(&tags)
+                  .err()
+                  .map(|error| ("Could not copy PGN:", error.to_string())),
+                1 => {
+                  let file_name = pgn_panel.get_file_name();
+                  match board.save_pgn(&tags, file_name) {
+                    Ok(()) => Some(("Saved the PGN to", file_name.to_string())),
+                    Err(error) => Some(("Could not save PGN:", error.to_string())),
+                  }
+                }
+                _ => match load_game(pgn_panel.get_file_name()) {
+                  Ok(game) => {
+                    move_list.set_game(&game);
+                    board.set_position(game.start.clone());
+                    side_panel.reload(board.get_position());
+                    None
+                  }
+                  Err(error) => Some(("Could not load PGN:", error.to_string())),
+                },
+              };
+            }
+          }
+
+          engine_panel.event_listener(&event);
+          move_line.event_listener(&event);
+          for (idx, button) in engine_buttons.iter_mut().enumerate() {
+            if button.event_listener(&event) {
+              // dropping the old engine makes it quit
+              engine = None;
+              analysed_fen = None;
+              if idx == 0 {
+                match Engine::start(engine_panel.get_path()) {
+                  Ok(started) => engine = Some(started),
+                  Err(error) => message = Some(("Could not start the engine:", error.to_string())),
+                }
+              }
+            }
+          }
+
+          chess960_number.event_listener(&event);
+          for (idx, button) in chess960_buttons.iter_mut().enumerate() {
+            if button.event_listener(&event) {
+              if idx == 1 {
+                chess960_number.set_number(get_random_number());
+              }
+              let number = chess960_number.get_number();
+              if number < POSITION_COUNT {
+                board.set_chess960_position(number);
+              } else {
+                message = Some((
+                  "Chess960 positions are numbered",
+                  format!("from 0 to {}", POSITION_COUNT - 1),
+                ));
+              }
+            }
+          }
+          if random_position.event_listener(&event) {
+            generator_dialog.open();
+          }
+          shredder_fen.event_listener(&event);
+          board.set_castling_notation(if shredder_fen.is_checked() {
+            CastlingNotation::Shredder
+          } else {
+            CastlingNotation::XFen
+          });
+          chess960.event_listener(&event);
+          if chess960.is_checked() != board.get_position().chess960 {
+            board.get_position_mut().set_chess960(chess960.is_checked());
+          }
+
+          analysis_mode.event_listener(&event);
+          if analysis_mode.is_checked() != board.is_analysis_mode() {
+            board.set_analysis_mode(analysis_mode.is_checked());
+          }
+
+          if move_list.event_listener(&event) {
+            if let Some(position) = move_list.get_position() {
+              board.set_position(position.clone());
+              board.set_annotations(move_list.get_annotations().cloned().unwrap_or_default());
+              side_panel.reload(board.get_position());
+            }
           }
         }
       }
     }
+    if let Some((position, annotations, mv)) = board.take_played_move() {
+      move_list.push_move(&position, &annotations, &mv);
+    }
+    side_panel.update(board.get_position_mut());
+    chess960.set_checked(board.get_position().chess960);
+    let edited = move_list
+      .get_position()
+      .is_some_and(|position| position != board.get_position());
+    move_list.set_editing(edited);
+    board.set_game_loaded(move_list.is_loaded());
+    if !edited {
+      move_list.set_annotations(board.get_annotations());
+    }
+    // typing into the panel is recorded once the input loses focus
+    if !side_panel.is_focused() && !side_panel.is_typing() {
+      history.record(board.get_position(), board.get_annotations());
+    }
+    let checked = board.get_position().validate();
+    if checked != problems {
+      confirm_copy = None;
+    }
+    problems = checked;
+    game_status = if problems.is_empty() {
+      get_game_status(board.get_position())
+    } else {
+      Vec::new()
+    };
+
+    if let Some(running) = engine.as_mut() {
+      // engines are only given legal positions, many crash on others
+      let fen = problems.is_empty().then(|| board.get_fen());
+      if fen != analysed_fen {
+        let result = match &fen {
+          Some(fen) => running.analyse(fen, board.get_position().chess960),
+          None => running.stop(),
+        };
+        if let Err(error) = result {
+          message = Some(("Could not talk to the engine:", error.to_string()));
+        }
+        analysed_fen = fen;
+      }
+      running.poll();
+      if !running.is_running() {
+        message = Some(("The engine stopped:", running.get_name().to_string()));
+        engine = None;
+      }
+    }
+    let is_parsed = matches!(&parsed_move_line, Some((position, text))
+      if position == board.get_position() && text == move_line.get_text());
+    if !is_parsed {
+      let (moves, error) = parse_move_line(board.get_position(), move_line.get_text());
+      board.set_move_line(&moves);
+      move_line_error = error;
+      parsed_move_line = Some((
+        board.get_position().clone(),
+        move_line.get_text().to_string(),
+      ));
+    }
+
+    engine_panel.update(engine.as_ref(), board.get_position(), problems.is_empty());
   }
 }
@@ -0,0 +1,316 @@
+use crate::{
+  attack::{
+    is_in_check, is_square_attacked, offset_square, BISHOP_DIRECTIONS, KING_OFFSETS,
+    KNIGHT_OFFSETS, ROOK_DIRECTIONS,
+  },
+  fen::{get_king_file, get_possible_castling},
+  position::{Piece, Position, COLUMNS, ROWS},
+};
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveKind {
+  Normal,
+  DoublePush,
+  EnPassant,
+  // `to` is the square the king ends up on
+  Castle { kingside: bool },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Move {
+  pub from: (usize, usize),
+  pub to: (usize, usize),
+  pub promotion: Option<Piece>,
+  pub kind: MoveKind,
+}
+
+impl Move {
+  fn new(from: (usize, usize), to: (usize, usize), kind: MoveKind) -> Move {
+    Move {
+      from,
+      to,
+      promotion: None,
+      kind,
+    }
+  }
+}
+
+fn get_back_row(player: usize) -> usize {
+  if player == 1 {
+    COLUMNS - 1
+  } else {
+    0
+  }
+}
+
+fn push_pawn_moves(
+  moves: &mut Vec<Move>,
+  from: (usize, usize),
+  to: (usize, usize),
+  kind: MoveKind,
+) {
+  // the last row for either side is the back row of the other one
+  if to.0 == 0 || to.0 == COLUMNS - 1 {
+    for piece in PROMOTION_PIECES {
+      moves.push(Move {
+        promotion: Some(piece),
+        ..Move::new(from, to, kind)
+      });
+    }
+  } else {
+    moves.push(Move::new(from, to, kind));
+  }
+}
+
+fn generate_pawn_moves(position: &Position, from: (usize, usize), moves: &mut Vec<Move>) {
+  let player = position.player;
+  let (direction, start_row) = if player == 1 {
+    (-1, COLUMNS - 2)
+  } else {
+    (1, 1)
+  };
+  if let Some(to) = offset_square(from, (direction, 0)) {
+    if position.get_piece(to).is_none() {
+      push_pawn_moves(moves, from, to, MoveKind::Normal);
+      if from.0 == start_row {
+        let double = offset_square(to, (direction, 0)).unwrap();
+        if position.get_piece(double).is_none() {
+          moves.push(Move::new(from, double, MoveKind::DoublePush));
+        }
+      }
+    }
+  }
+  for side in [-1, 1] {
+    if let Some(to) = offset_square(from, (direction, side)) {
+      match position.get_piece(to) {
+        Some((owner, _)) if owner != player => push_pawn_moves(moves, from, to, MoveKind::Normal),
+        None if position.en_passant == Some(to) => {
+          moves.push(Move::new(from, to, MoveKind::EnPassant))
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+fn generate_step_moves(
+  position: &Position,
+  from: (usize, usize),
+  offsets: &[(i32, i32)],
+  moves: &mut Vec<Move>,
+) {
+  for offset in offsets {
+    if let Some(to) = offset_square(from, *offset) {
+      match position.get_piece(to) {
+        Some((owner, _)) if owner == position.player => {}
+        _ => moves.push(Move::new(from, to, MoveKind::Normal)),
+      }
+    }
+  }
+}
+
+fn generate_sliding_moves(
+  position: &Position,
+  from: (usize, usize),
+  directions: &[(i32, i32)],
+  moves: &mut Vec<Move>,
+) {
+  for direction in directions {
+    let mut curr = offset_square(from, *direction);
+    while let Some(to) = curr {
+      match position.get_piece(to) {
+        None => moves.push(Move::new(from, to, MoveKind::Normal)),
+        Some((owner, _)) => {
+          if owner != position.player {
+            moves.push(Move::new(from, to, MoveKind::Normal));
+          }
+          break;
+        }
+      }
+      curr = offset_square(to, *direction);
+    }
+  }
+}
+
+// the square of the rook a castling move takes along
+pub fn get_castling_rook(position: &Position, mv: &Move) -> Option<(usize, usize)> {
+  let files = position.castling_files[position.player];
+  match mv.kind {
+    MoveKind::Castle { kingside: true } => Some((mv.from.0, files.0)),
+    MoveKind::Castle { kingside: false } => Some((mv.from.0, files.1)),
+    _ => None,
+  }
+}
+
+// castling needs the right, the squares the king and the rook cross to
+// be empty and the king not to start on, pass or land on an attacked
+// square, the king ends up on the g or c file and the rook next to it
+// wherever they started, which covers Chess960 too
+fn generate_castling_moves(position: &Position, moves: &mut Vec<Move>) {
+  let player = position.player;
+  let row = get_back_row(player);
+  let rights = if player == 1 {
+    position.castle_white
+  } else {
+    position.castle_black
+  };
+  let files = position.castling_files[player];
+  let possible = get_possible_castling(&position.board, player, files, position.chess960);
+  let Some(king) = get_king_file(&position.board, player) else {
+    return;
+  };
+  for (allowed, kingside, rook, king_to, rook_to) in [
+    (rights.0 && possible.0, true, files.0, 6, 5),
+    (rights.1 && possible.1, false, files.1, 2, 3),
+  ] {
+    if !allowed {
+      continue;
+    }
+    let is_empty = (king.min(rook).min(king_to).min(rook_to)
+      ..=king.max(rook).max(king_to).max(rook_to))
+      .all(|j| j == king || j == rook || position.get_piece((row, j)).is_none());
+    let is_safe = (king.min(king_to)..=king.max(king_to))
+      .all(|j| !is_square_attacked(&position.board, (row, j), 1 - player));
+    if is_empty && is_safe {
+      moves.push(Move::new(
+        (row, king),
+        (row, king_to),
+        MoveKind::Castle { kingside },
+      ));
+    }
+  }
+}
+
+// moves that follow the piece movement rules but may leave the king in check
+pub fn generate_pseudo_legal_moves(position: &Position) -> Vec<Move> {
+  let mut moves = Vec::new();
+  for i in 0..COLUMNS {
+    for j in 0..ROWS {
+      let from = (i, j);
+      match position.get_piece(from) {
+        Some((owner, piece)) if owner == position.player => match piece {
+          Piece::Pawn => generate_pawn_moves(position, from, &mut moves),
+          Piece::Knight => generate_step_moves(position, from, &KNIGHT_OFFSETS, &mut moves),
+          Piece::King => generate_step_moves(position, from, &KING_OFFSETS, &mut moves),
+          Piece::Bishop => generate_sliding_moves(position, from, &BISHOP_DIRECTIONS, &mut moves),
+          Piece::Rook => generate_sliding_moves(position, from, &ROOK_DIRECTIONS, &mut moves),
+          Piece::Queen => {
+            generate_sliding_moves(position, from, &BISHOP_DIRECTIONS, &mut moves);
+            generate_sliding_moves(position, from, &ROOK_DIRECTIONS, &mut moves);
+          }
+          Piece::None => {}
+        },
+        _ => {}
+      }
+    }
+  }
+  generate_castling_moves(position, &mut moves);
+  moves
+}
+
+pub fn generate_legal_moves(position: &Position) -> Vec<Move> {
+  generate_pseudo_legal_moves(position)
+    .into_iter()
+    .filter(|mv| {
+      let mut next = position.clone();
+      make_move(&mut next, mv);
+      !is_in_check(&next.board, position.player)
+    })
+    .collect()
+}
+
+// a rook leaving or captured on its castling square loses that right
+fn remove_castling_right(position: &mut Position, square: (usize, usize)) {
+  let player = match square.0 {
+    0 => 0,
+    row if row == COLUMNS - 1 => 1,
+    _ => return,
+  };
+  let files = position.castling_files[player];
+  let rights = if player == 1 {
+    &mut position.castle_white
+  } else {
+    &mut position.castle_black
+  };
+  if square.1 == files.0 {
+    rights.0 = false;
+  }
+  if square.1 == files.1 {
+    rights.1 = false;
+  }
+}
+
+// plays a move without checking that it is legal
+pub fn make_move(position: &mut Position, mv: &Move) {
+  let player = position.player;
+  let (_, piece) = position
+    .get_piece(mv.from)
+    .expect("a move needs a piece on its starting square");
+  let castling_rook = get_castling_rook(position, mv);
+  let is_capture = match mv.kind {
+    MoveKind::Castle { .. } => false,
+    MoveKind::EnPassant => true,
+    _ => position.get_piece(mv.to).is_some(),
+  };
+
+  // in Chess960 the king can land where the rook stood, so the rook
+  // leaves first
+  if let Some(rook) = castling_rook {
+    position.remove_piece(rook);
+  }
+  position.remove_piece(mv.from);
+  position.put_piece(mv.to, player, mv.promotion.unwrap_or(piece));
+  match mv.kind {
+    MoveKind::EnPassant => position.remove_piece((mv.from.0, mv.to.1)),
+    MoveKind::Castle { kingside } => {
+      let rook_to = if kingside { 5 } else { 3 };
+      position.put_piece((mv.from.0, rook_to), player, Piece::Rook);
+    }
+    _ => {}
+  }
+
+  if piece == Piece::King {
+    if player == 1 {
+      position.castle_white = (false, false);
+    } else {
+      position.castle_black = (false, false);
+    }
+  }
+  remove_castling_right(position, mv.from);
+  remove_castling_right(position, mv.to);
+  position.en_passant = match mv.kind {
+    MoveKind::DoublePush => Some(((mv.from.0 + mv.to.0) / 2, mv.from.1)),
+    _ => None,
+  };
+  if piece == Piece::Pawn || is_capture {
+    position.halfmove_clock = 0;
+  } else {
+    position.halfmove_clock += 1;
+  }
+  if player == 0 {
+    position.fullmove_number += 1;
+  }
+  position.player = 1 - player;
+}
+
+// counts the leaf nodes of the move tree, used to check the generator
+// against known results
+pub fn perft(position: &Position, depth: usize) -> u64 {
+  if depth == 0 {
+    return 1;
+  }
+  let moves = generate_legal_moves(position);
+  if depth == 1 {
+    return moves.len() as u64;
+  }
+  moves
+    .iter()
+    .map(|mv| {
+      let mut next = position.clone();
+      make_move(&mut next, mv);
+      perft(&next, depth - 1)
+    })
+    .sum()
+}
@@ -0,0 +1,280 @@
+use crate::{
+  annotation::Annotations,
+  movegen::{make_move, Move},
+  pgn::Game,
+  position::Position,
+  san::get_san,
+  Rect,
+};
+use allegro::{Color, Core, Event, KeyCode};
+use allegro_font::{Font, FontAlign, FontDrawing};
+use allegro_primitives::PrimitivesAddon;
+
+const NUMBER_WIDTH: f32 = 40.0;
+const PADDING_X: f32 = 6.0;
+
+// the moves of a loaded game, stepping through them shows the positions
+// on the board and editing the board leaves the game until the next step
+pub struct MoveList {
+  rect: Rect,
+  line_height: f32,
+  title: String,
+  result: String,
+  sans: Vec<String>,
+  positions: Vec<Position>,
+  // the arrows and highlights drawn on each position
+  annotations: Vec<Annotations>,
+  ply: usize,
+  hovered_ply: Option<usize>,
+  is_editing: bool,
+}
+
+impl MoveList {
+  pub fn new(rect: Rect, font: &Font) -> MoveList {
+    MoveList {
+      rect,
+      line_height: font.get_line_height() as f32,
+      title: String::new(),
+      result: String::new(),
+      sans: Vec::new(),
+      positions: Vec::new(),
+      annotations: Vec::new(),
+      ply: 0,
+      hovered_ply: None,
+      is_editing: false,
+    }
+  }
+
+  pub fn set_game(&mut self, game: &Game) {
+    self.positions = game.get_positions();
+    self.annotations = vec![Annotations::new(); self.positions.len()];
+    self.sans = game
+      .moves
+      .iter()
+      .zip(self.positions.iter())
+      .map(|(mv, position)| get_san(position, mv))
+      .collect();
+    self.title = format!(
+      "{} - {}",
+      game.get_tag("White").unwrap_or("?"),
+      game.get_tag("Black").unwrap_or("?")
+    );
+    self.result = game.result.clone();
+    self.ply = 0;
+    self.is_editing = false;
+  }
+
+  // adds a move played on the board after the current ply, replacing the
+  // moves that followed it, a move from any other position starts a new
+  // line
+  pub fn push_move(&mut self, position: &Position, annotations: &Annotations, mv: &Move) {
+    if self.is_editing || self.get_position() != Some(position) {
+      self.positions = vec![position.clone()];
+      self.annotations = vec![Annotations::new()];
+      self.sans.clear();
+      self.title = "Analysis".to_string();
+      self.result = "*".to_string();
+      self.ply = 0;
+      self.is_editing = false;
+    }
+    self.positions.truncate(self.ply + 1);
+    self.annotations.truncate(self.ply + 1);
+    self.annotations[self.ply] = annotations.clone();
+    self.sans.truncate(self.ply);
+    let mut next = position.clone();
+    make_move(&mut next, mv);
+    self.sans.push(get_san(position, mv));
+    self.positions.push(next);
+    self.annotations.push(Annotations::new());
+    self.ply += 1;
+  }
+
+  pub fn is_loaded(&self) -> bool {
+    !self.positions.is_empty()
+  }
+
+  // the position at the current ply, the board shows it unless it is
+  // being edited
+  pub fn get_position(&self) -> Option<&Position> {
+    self.positions.get(self.ply)
+  }
+
+  pub fn get_annotations(&self) -> Option<&Annotations> {
+    self.annotations.get(self.ply)
+  }
+
+  // keeps what is drawn on the board with the position at the current
+  // ply, while the board shows it
+  pub fn set_annotations(&mut self, annotations: &Annotations) {
+    if let Some(current) = self.annotations.get_mut(self.ply) {
+      *current = annotations.clone();
+    }
+  }
+
+  pub fn set_editing(&mut self, editing: bool) {
+    self.is_editing = editing;
+  }
+
+  // rows hold a white and a black move, a game starting with black to
+  // move leaves the first white entry empty
+  fn get_row_and_column(&self, ply: usize) -> (usize, usize) {
+    let offset = (self.positions[0].player == 0) as usize;
+    ((ply - 1 + offset) / 2, (ply - 1 + offset) % 2)
+  }
+
+  fn get_visible_rows(&self) -> usize {
+    ((self.rect.height - self.line_height * 3.0) / self.line_height) as usize
+  }
+
+  // scrolls just far enough to keep the current move in view
+  fn get_first_row(&self) -> usize {
+    if self.ply == 0 {
+      return 0;
+    }
+    let (row, _) = self.get_row_and_column(self.ply);
+    (row + 1).saturating_sub(self.get_visible_rows())
+  }
+
+  fn get_entry_rect(&self, ply: usize) -> Rect {
+    let (row, column) = self.get_row_and_column(ply);
+    let width = (self.rect.width - NUMBER_WIDTH) / 2.0;
+    Rect::new(
+      self.rect.x + NUMBER_WIDTH + width * column as f32,
+      self.rect.y + self.line_height * (3.0 + (row - self.get_first_row()) as f32),
+      width,
+      self.line_height,
+    )
+  }
+
+  fn get_visible_plies(&self) -> Vec<usize> {
+    let (first_row, visible_rows) = (self.get_first_row(), self.get_visible_rows());
+    (1..=self.sans.len())
+      .filter(|&ply| {
+        let (row, _) = self.get_row_and_column(ply);
+        row >= first_row && row < first_row + visible_rows
+      })
+      .collect()
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    let grey = Color::from_rgb(154, 153, 153);
+    core.draw_text(
+      font,
+      grey,
+      self.rect.x,
+      self.rect.y,
+      FontAlign::Left,
+      "Moves",
+    );
+    if !self.is_loaded() {
+      core.draw_text(
+        font,
+        grey,
+        self.rect.x,
+        self.rect.y + self.line_height * 1.5,
+        FontAlign::Left,
+        "Load a PGN file to view a game",
+      );
+      return;
+    }
+    core.draw_text(
+      font,
+      Color::from_rgb(255, 255, 255),
+      self.rect.x,
+      self.rect.y + self.line_height * 1.5,
+      FontAlign::Left,
+      self.title.as_str(),
+    );
+    let mut last_y = self.rect.y + self.line_height * 3.0;
+    for ply in self.get_visible_plies() {
+      let rect = self.get_entry_rect(ply);
+      if ply == self.ply || self.hovered_ply == Some(ply) {
+        primitives.draw_filled_rectangle(
+          rect.x,
+          rect.y,
+          rect.x + rect.width,
+          rect.y + rect.height,
+          if ply == self.ply {
+            Color::from_rgb(67, 111, 157)
+          } else {
+            Color::from_rgb(54, 52, 48)
+          },
+        );
+      }
+      let position = &self.positions[ply - 1];
+      if position.player == 1 || ply == 1 {
+        let number = if position.player == 1 {
+          format!("{}.", position.fullmove_number)
+        } else {
+          format!("{}...", position.fullmove_number)
+        };
+        core.draw_text(font, grey, self.rect.x, rect.y, FontAlign::Left, &number);
+      }
+      core.draw_text(
+        font,
+        Color::from_rgb(255, 255, 255),
+        rect.x + PADDING_X,
+        rect.y,
+        FontAlign::Left,
+        self.sans[ply - 1].as_str(),
+      );
+      last_y = rect.y + self.line_height;
+    }
+    if self.get_visible_plies().last() == Some(&self.sans.len()) {
+      core.draw_text(
+        font,
+        grey,
+        self.rect.x,
+        last_y,
+        FontAlign::Left,
+        &self.result,
+      );
+      last_y += self.line_height;
+    }
+    if self.is_editing {
+      core.draw_text(
+        font,
+        Color::from_rgb(224, 108, 117),
+        self.rect.x,
+        last_y + self.line_height * 0.5,
+        FontAlign::Left,
+        "Edited, use the arrow keys to go back",
+      );
+    }
+  }
+
+  // true when the current ply changed, the board should then show the
+  // position at the new ply
+  pub fn event_listener(&mut self, event: &Event) -> bool {
+    if !self.is_loaded() {
+      return false;
+    }
+    let last_ply = self.sans.len();
+    let ply = match event {
+      Event::MouseAxes { x, y, .. } => {
+        self.hovered_ply = self.get_visible_plies().into_iter().find(|&ply| {
+          self
+            .get_entry_rect(ply)
+            .contains_point(*x as f32, *y as f32)
+        });
+        return false;
+      }
+      Event::MouseButtonDown { .. } => match self.hovered_ply {
+        Some(ply) => ply,
+        None => return false,
+      },
+      Event::KeyChar { keycode, .. } => match keycode {
+        KeyCode::Left => self.ply.saturating_sub(1),
+        KeyCode::Right => (self.ply + 1).min(last_ply),
+        KeyCode::Home => 0,
+        KeyCode::End => last_ply,
+        _ => return false,
+      },
+      _ => return false,
+    };
+    let changed = ply != self.ply || self.is_editing;
+    self.ply = ply;
+    self.is_editing = false;
+    changed
+  }
+}
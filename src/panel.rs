@@ -0,0 +1,469 @@
+use crate::{
+  checkbox::{CheckBox, CheckBoxGroup},
+  dropdown::Dropdown,
+  engine::{get_pv_san, Engine},
+  input::TextInput,
+  pgn::PgnTags,
+  position::Position,
+  Rect,
+};
+use allegro::{Color, Core, Event};
+use allegro_font::{Font, FontAlign, FontDrawing};
+use allegro_primitives::PrimitivesAddon;
+
+const MARGIN: f32 = 20.0;
+
+const EN_PASSANT_ITEMS: [&str; 17] = [
+  "No en passant",
+  "En passant on a6",
+  "En passant on b6",
+  "En passant on c6",
+  "En passant on d6",
+  "En passant on e6",
+  "En passant on f6",
+  "En passant on g6",
+  "En passant on h6",
+  "En passant on a3",
+  "En passant on b3",
+  "En passant on c3",
+  "En passant on d3",
+  "En passant on e3",
+  "En passant on f3",
+  "En passant on g3",
+  "En passant on h3",
+];
+
+fn get_en_passant_item(square: (usize, usize)) -> &'static str {
+  let (i, j) = square;
+  EN_PASSANT_ITEMS[if i == 2 { 1 + j } else { 9 + j }]
+}
+
+// the widgets for the parts of the position that are not on the board,
+// they write every change into the position and are refreshed from it
+// with `update`
+pub struct SidePanel<'a> {
+  side_to_move: Dropdown<'a>,
+  en_passant: Dropdown<'a>,
+  en_passant_squares: Vec<(usize, usize)>,
+  halfmove_clock: TextInput<'a>,
+  fullmove_number: TextInput<'a>,
+  auto_castling: CheckBox<'a>,
+  // the castling rights the board allowed at the last update
+  possible_castling: ((bool, bool), (bool, bool)),
+  castle_white: CheckBoxGroup<'a>,
+  castle_black: CheckBoxGroup<'a>,
+}
+
+impl<'a> SidePanel<'a> {
+  pub fn new(rect: Rect, font: &Font) -> SidePanel<'a> {
+    let (x, y, width, height) = (rect.x, rect.y, rect.width, rect.height);
+    let side_to_move = Dropdown::new(rect, vec!["Black to play", "White to play"], 1, font);
+    let en_passant = Dropdown::new(
+      Rect::new(x, y + height + MARGIN, width, height),
+      vec![EN_PASSANT_ITEMS[0]],
+      0,
+      font,
+    );
+
+    let counter_width = (width - 10.0) / 2.0;
+    let counter_y = y + (height + MARGIN) * 2.0;
+    let halfmove_clock = TextInput::new_numeric(
+      Rect::new(x, counter_y, counter_width, height),
+      "Halfmove clock",
+      0,
+      font,
+    );
+    let fullmove_number = TextInput::new_numeric(
+      Rect::new(x + counter_width + 10.0, counter_y, counter_width, height),
+      "Fullmove number",
+      1,
+      font,
+    );
+
+    let auto_castling = CheckBox::new(
+      x + 20.0,
+      halfmove_clock.get_next_y() + 30.0,
+      20.0,
+      "Auto castling rights",
+    );
+    let castle_white = CheckBoxGroup::new(
+      "White",
+      x + 20.0,
+      auto_castling.get_next_y() + 25.0,
+      20.0,
+      vec!["O-O", "O-O-O"],
+      font,
+    );
+    let castle_black = CheckBoxGroup::new(
+      "Black ",
+      x + 20.0,
+      castle_white.get_next_y(),
+      20.0,
+      vec!["O-O", "O-O-O"],
+      font,
+    );
+    SidePanel {
+      side_to_move,
+      en_passant,
+      en_passant_squares: Vec::new(),
+      halfmove_clock,
+      fullmove_number,
+      auto_castling,
+      possible_castling: ((false, false), (false, false)),
+      castle_white,
+      castle_black,
+    }
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    self.auto_castling.draw(core, primitives, font);
+    self.castle_white.draw(core, primitives, font);
+    self.castle_black.draw(core, primitives, font);
+    self.halfmove_clock.draw(core, primitives, font);
+    self.fullmove_number.draw(core, primitives, font);
+  }
+
+  // the open list of a dropdown covers the widgets below it, so the
+  // dropdowns are drawn separately after everything else
+  pub fn draw_dropdowns(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    if self.en_passant.is_focused() {
+      self.side_to_move.draw(core, primitives, font);
+      self.en_passant.draw(core, primitives, font);
+    } else {
+      self.en_passant.draw(core, primitives, font);
+      self.side_to_move.draw(core, primitives, font);
+    }
+  }
+
+  // true while a dropdown is open, every event should go to the panel then
+  pub fn is_focused(&self) -> bool {
+    self.side_to_move.is_focused() || self.en_passant.is_focused()
+  }
+
+  // takes a position that was loaded rather than edited as it is, so
+  // auto castling does not add rights to it
+  pub fn reload(&mut self, position: &Position) {
+    self.possible_castling = position.get_possible_castling();
+  }
+
+  // true while one of the counters is being typed into
+  pub fn is_typing(&self) -> bool {
+    self.halfmove_clock.is_focused() || self.fullmove_number.is_focused()
+  }
+
+  pub fn unfocus_inputs(&mut self) {
+    self.halfmove_clock.set_focused(false);
+    self.fullmove_number.set_focused(false);
+  }
+
+  pub fn event_listener(&mut self, event: &Event, position: &mut Position) -> bool {
+    let mut handled = self.is_focused();
+    if self.side_to_move.is_focused() {
+      self.side_to_move.event_listener(event);
+    } else if self.en_passant.is_focused() {
+      let prev_idx = self.en_passant.get_selected_item_idx();
+      self.en_passant.event_listener(event);
+      let idx = self.en_passant.get_selected_item_idx();
+      if idx != prev_idx {
+        // the first item is "-", the squares follow it
+        position.en_passant = usize::try_from(idx)
+          .ok()
+          .and_then(|idx| idx.checked_sub(1))
+          .and_then(|idx| self.en_passant_squares.get(idx))
+          .copied();
+      }
+    } else {
+      self.side_to_move.event_listener(event);
+      self.en_passant.event_listener(event);
+      let was_auto = self.auto_castling.is_checked();
+      self.auto_castling.event_listener(event);
+      if self.auto_castling.is_checked() && !was_auto {
+        let (white, black) = position.get_possible_castling();
+        self.castle_white.set_values(white);
+        self.castle_black.set_values(black);
+      }
+      if !self.castle_white.event_listener(event) {
+        self.castle_black.event_listener(event);
+      }
+      // the counters are only written back once typed into, a loaded
+      // counter too large for its box would be clamped otherwise
+      let halfmove_clock = self.halfmove_clock.get_number();
+      let fullmove_number = self.fullmove_number.get_number();
+      handled |= self.halfmove_clock.event_listener(event);
+      handled |= self.fullmove_number.event_listener(event);
+      if self.halfmove_clock.get_number() != halfmove_clock {
+        position.halfmove_clock = self.halfmove_clock.get_number();
+      }
+      if self.fullmove_number.get_number() != fullmove_number {
+        position.fullmove_number = self.fullmove_number.get_number().max(1);
+      }
+    }
+
+    position.player = self.side_to_move.get_selected_item_idx() as usize;
+    position.castle_white = self.castle_white.get_values();
+    position.castle_black = self.castle_black.get_values();
+    position.update_en_passant();
+    handled
+  }
+
+  // in auto mode, rights turn on once the king and rook reach their
+  // starting squares and off when either leaves, they can still be
+  // turned off by hand
+  fn update_castling(&mut self, position: &mut Position) {
+    position.update_castling_files();
+    let possible = position.get_possible_castling();
+    if self.auto_castling.is_checked() {
+      position.update_auto_castling(self.possible_castling);
+      let (white, black) = possible;
+      self.castle_white.set_enabled(white);
+      self.castle_black.set_enabled(black);
+    } else {
+      self.castle_white.set_enabled((true, true));
+      self.castle_black.set_enabled((true, true));
+    }
+    self.possible_castling = possible;
+  }
+
+  pub fn update(&mut self, position: &mut Position) {
+    self.update_castling(position);
+    self
+      .side_to_move
+      .set_selected_item_idx(position.player as i32);
+    self.castle_white.set_values(position.castle_white);
+    self.castle_black.set_values(position.castle_black);
+    // leave the text alone while it is being typed
+    if !self.halfmove_clock.is_focused() {
+      self.halfmove_clock.set_number(position.halfmove_clock);
+    }
+    if !self.fullmove_number.is_focused() {
+      self.fullmove_number.set_number(position.fullmove_number);
+    }
+
+    let squares = position.get_en_passant_squares();
+    if squares != self.en_passant_squares {
+      self.en_passant_squares = squares;
+      let mut items = vec![EN_PASSANT_ITEMS[0]];
+      items.extend(
+        self
+          .en_passant_squares
+          .iter()
+          .map(|square| get_en_passant_item(*square)),
+      );
+      self.en_passant.set_items(items);
+    }
+    let selected = position
+      .en_passant
+      .and_then(|square| {
+        self
+          .en_passant_squares
+          .iter()
+          .position(|curr| *curr == square)
+      })
+      .map_or(0, |idx| idx + 1);
+    self.en_passant.set_selected_item_idx(selected as i32);
+  }
+
+  pub fn get_next_y(&self) -> f32 {
+    self.castle_black.get_next_y()
+  }
+}
+
+// the tags and the file name used when exporting a PGN
+pub struct PgnPanel<'a> {
+  event: TextInput<'a>,
+  white: TextInput<'a>,
+  black: TextInput<'a>,
+  annotator: TextInput<'a>,
+  file_name: TextInput<'a>,
+}
+
+impl<'a> PgnPanel<'a> {
+  pub fn new(rect: Rect, font: &Font) -> PgnPanel<'a> {
+    let (x, width, height) = (rect.x, rect.width, rect.height);
+    let tags = PgnTags::default();
+    let event = TextInput::new(rect, "Event", tags.event.as_str(), font);
+    let half_width = (width - 10.0) / 2.0;
+    let players_y = event.get_next_y() + MARGIN;
+    let white = TextInput::new(
+      Rect::new(x, players_y, half_width, height),
+      "White",
+      tags.white.as_str(),
+      font,
+    );
+    let black = TextInput::new(
+      Rect::new(x + half_width + 10.0, players_y, half_width, height),
+      "Black",
+      tags.black.as_str(),
+      font,
+    );
+    let annotator = TextInput::new(
+      Rect::new(x, white.get_next_y() + MARGIN, width, height),
+      "Annotator",
+      tags.annotator.as_str(),
+      font,
+    );
+    let file_name = TextInput::new(
+      Rect::new(x, annotator.get_next_y() + MARGIN, width, height),
+      "File",
+      "position.pgn",
+      font,
+    );
+    PgnPanel {
+      event,
+      white,
+      black,
+      annotator,
+      file_name,
+    }
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    self.event.draw(core, primitives, font);
+    self.white.draw(core, primitives, font);
+    self.black.draw(core, primitives, font);
+    self.annotator.draw(core, primitives, font);
+    self.file_name.draw(core, primitives, font);
+  }
+
+  pub fn unfocus_inputs(&mut self) {
+    self.event.set_focused(false);
+    self.white.set_focused(false);
+    self.black.set_focused(false);
+    self.annotator.set_focused(false);
+    self.file_name.set_focused(false);
+  }
+
+  pub fn event_listener(&mut self, event: &Event) -> bool {
+    let mut handled = self.event.event_listener(event);
+    handled |= self.white.event_listener(event);
+    handled |= self.black.event_listener(event);
+    handled |= self.annotator.event_listener(event);
+    handled |= self.file_name.event_listener(event);
+    handled
+  }
+
+  pub fn get_tags(&self) -> PgnTags {
+    PgnTags {
+      event: self.event.get_text().to_string(),
+      white: self.white.get_text().to_string(),
+      black: self.black.get_text().to_string(),
+      annotator: self.annotator.get_text().to_string(),
+    }
+  }
+
+  pub fn get_file_name(&self) -> &str {
+    self.file_name.get_text().trim()
+  }
+
+  pub fn get_next_y(&self) -> f32 {
+    self.file_name.get_next_y() + MARGIN
+  }
+}
+
+// the engine path and what the engine thinks of the position, the
+// start and stop buttons go in the row below the path
+pub struct EnginePanel<'a> {
+  path: TextInput<'a>,
+  lines: Vec<String>,
+  lines_rect: Rect,
+}
+
+impl<'a> EnginePanel<'a> {
+  pub fn new(rect: Rect, font: &Font) -> EnginePanel<'a> {
+    let path = TextInput::new(rect.clone(), "Engine path", "stockfish", font);
+    let lines_y = path.get_next_y() + rect.height + MARGIN * 2.0;
+    EnginePanel {
+      lines: vec!["No engine running".to_string()],
+      lines_rect: Rect::new(
+        rect.x,
+        lines_y,
+        rect.width,
+        font.get_line_height() as f32 * 5.0,
+      ),
+      path,
+    }
+  }
+
+  pub fn draw(&self, core: &Core, primitives: &PrimitivesAddon, font: &Font) {
+    self.path.draw(core, primitives, font);
+    // long lines are wrapped at spaces, whatever does not fit is cut
+    let line_height = font.get_line_height() as f32;
+    let mut rows: Vec<(usize, String)> = Vec::new();
+    for (idx, line) in self.lines.iter().enumerate() {
+      let mut row = String::new();
+      for word in line.split(' ') {
+        let next = if row.is_empty() {
+          word.to_string()
+        } else {
+          format!("{} {}", row, word)
+        };
+        if font.get_text_width(next.as_str()) as f32 > self.lines_rect.width && !row.is_empty() {
+          rows.push((idx, row));
+          row = word.to_string();
+        } else {
+          row = next;
+        }
+      }
+      rows.push((idx, row));
+    }
+    let max_rows = (self.lines_rect.height / line_height) as usize;
+    for (row_idx, (idx, row)) in rows.iter().take(max_rows).enumerate() {
+      core.draw_text(
+        font,
+        if *idx == 0 {
+          Color::from_rgb(154, 153, 153)
+        } else {
+          Color::from_rgb(255, 255, 255)
+        },
+        self.lines_rect.x,
+        self.lines_rect.y + line_height * row_idx as f32,
+        FontAlign::Left,
+        row.as_str(),
+      );
+    }
+  }
+
+  pub fn event_listener(&mut self, event: &Event) -> bool {
+    self.path.event_listener(event)
+  }
+
+  pub fn unfocus_inputs(&mut self) {
+    self.path.set_focused(false);
+  }
+
+  pub fn get_path(&self) -> &str {
+    self.path.get_text().trim()
+  }
+
+  pub fn update(&mut self, engine: Option<&Engine>, position: &Position, is_legal: bool) {
+    self.lines = match engine {
+      None => vec!["No engine running".to_string()],
+      Some(engine) if !is_legal => vec![
+        engine.get_name().to_string(),
+        "Waiting for a legal position".to_string(),
+      ],
+      Some(engine) => match engine.get_info() {
+        None => vec![engine.get_name().to_string(), "Thinking...".to_string()],
+        Some(info) => {
+          let score = info
+            .score
+            .map(|score| score.for_white(position.player).to_string())
+            .unwrap_or_default();
+          vec![
+            engine.get_name().to_string(),
+            format!("Depth {}   {}", info.depth, score),
+            get_pv_san(position, &info.pv),
+          ]
+        }
+      },
+    };
+  }
+
+  pub fn get_buttons_y(&self) -> f32 {
+    self.path.get_next_y() + MARGIN
+  }
+
+  pub fn get_next_y(&self) -> f32 {
+    self.lines_rect.y + self.lines_rect.height + MARGIN
+  }
+}
@@ -0,0 +1,247 @@
+use crate::{
+  annotation::Annotations,
+  fen::FenError,
+  movegen::{make_move, Move},
+  position::Position,
+  san::parse_san,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum PgnError {
+  InvalidTag(String),
+  InvalidFen(FenError),
+  IllegalMove { move_number: u32, san: String },
+  UnclosedComment,
+  UnbalancedVariation,
+}
+
+impl fmt::Display for PgnError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PgnError::InvalidTag(tag) => write!(f, "invalid tag '{}'", tag),
+      PgnError::InvalidFen(error) => write!(f, "invalid FEN tag, {}", error),
+      PgnError::IllegalMove { move_number, san } => {
+        write!(f, "illegal move {} at move {}", san, move_number)
+      }
+      PgnError::UnclosedComment => write!(f, "a comment is not closed"),
+      PgnError::UnbalancedVariation => write!(f, "the variations are not balanced"),
+    }
+  }
+}
+
+impl std::error::Error for PgnError {}
+
+// the main line of a game, comments, NAGs and variations are dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+  pub tags: Vec<(String, String)>,
+  pub start: Position,
+  pub moves: Vec<Move>,
+  pub result: String,
+}
+
+impl Game {
+  pub fn get_tag(&self, name: &str) -> Option<&str> {
+    self
+      .tags
+      .iter()
+      .find(|(tag, _)| tag == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  // the starting position followed by the position after every move
+  pub fn get_positions(&self) -> Vec<Position> {
+    let mut positions = vec![self.start.clone()];
+    for mv in self.moves.iter() {
+      let mut next = positions.last().unwrap().clone();
+      make_move(&mut next, mv);
+      positions.push(next);
+    }
+    positions
+  }
+}
+
+// the tags that can be edited before exporting, the rest of the seven
+// tag roster is left unknown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnTags {
+  pub event: String,
+  pub white: String,
+  pub black: String,
+  pub annotator: String,
+}
+
+impl Default for PgnTags {
+  fn default() -> PgnTags {
+    PgnTags {
+      event: "?".to_string(),
+      white: "?".to_string(),
+      black: "?".to_string(),
+      annotator: String::new(),
+    }
+  }
+}
+
+fn get_tag(name: &str, value: &str) -> String {
+  let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+  format!("[{} \"{}\"]\n", name, value)
+}
+
+// a game without moves that starts from the position, which is how PGN
+// stores a setup position, arrows and highlights go in a comment
+pub fn generate_pgn(position: &Position, tags: &PgnTags, annotations: &Annotations) -> String {
+  let or_unknown = |value: &str| -> String {
+    match value.trim() {
+      "" => "?".to_string(),
+      value => value.to_string(),
+    }
+  };
+  let mut pgn = String::new();
+  pgn += get_tag("Event", or_unknown(&tags.event).as_str()).as_str();
+  pgn += get_tag("Site", "?").as_str();
+  pgn += get_tag("Date", "????.??.??").as_str();
+  pgn += get_tag("Round", "-").as_str();
+  pgn += get_tag("White", or_unknown(&tags.white).as_str()).as_str();
+  pgn += get_tag("Black", or_unknown(&tags.black).as_str()).as_str();
+  pgn += get_tag("Result", "*").as_str();
+  if !tags.annotator.trim().is_empty() {
+    pgn += get_tag("Annotator", tags.annotator.trim()).as_str();
+  }
+  if position.chess960 {
+    pgn += get_tag("Variant", "Chess960").as_str();
+  }
+  pgn += get_tag("SetUp", "1").as_str();
+  pgn += get_tag("FEN", position.to_fen().as_str()).as_str();
+  pgn.push('\n');
+  if let Some(comment) = annotations.to_pgn_comment() {
+    pgn += comment.as_str();
+    pgn.push(' ');
+  }
+  pgn += "*\n";
+  pgn
+}
+
+// `Name "value"`, the text between the brackets of a tag pair
+fn parse_tag(tag: &str) -> Result<(String, String), PgnError> {
+  let invalid = || PgnError::InvalidTag(tag.to_string());
+  let (name, value) = tag
+    .trim()
+    .split_once(char::is_whitespace)
+    .ok_or_else(invalid)?;
+  let value = value
+    .trim()
+    .strip_prefix('"')
+    .and_then(|value| value.strip_suffix('"'))
+    .ok_or_else(invalid)?;
+  let mut unescaped = String::new();
+  let mut characters = value.chars();
+  while let Some(character) = characters.next() {
+    match character {
+      '\\' => unescaped.extend(characters.next()),
+      _ => unescaped.push(character),
+    }
+  }
+  Ok((name.to_string(), unescaped))
+}
+
+// reads the first game of a PGN file, the moves are checked against the
+// position they are played in
+pub fn parse_pgn(pgn: &str) -> Result<Game, PgnError> {
+  let mut tags = Vec::new();
+  let mut sans: Vec<String> = Vec::new();
+  let mut result = "*".to_string();
+  let mut depth = 0;
+  let mut characters = pgn.chars().peekable();
+  while let Some(character) = characters.next() {
+    match character {
+      '[' if depth == 0 => {
+        // a tag after the movetext starts the next game
+        if !sans.is_empty() {
+          break;
+        }
+        let mut tag = String::new();
+        let mut in_quotes = false;
+        loop {
+          let next = characters
+            .next()
+            .ok_or_else(|| PgnError::InvalidTag(tag.clone()))?;
+          match next {
+            ']' if !in_quotes => break,
+            '"' => in_quotes = !in_quotes,
+            '\\' if in_quotes => {
+              tag.push(next);
+              tag.extend(characters.next());
+              continue;
+            }
+            _ => {}
+          }
+          tag.push(next);
+        }
+        tags.push(parse_tag(tag.as_str())?);
+      }
+      '{' => {
+        if !characters.by_ref().any(|c| c == '}') {
+          return Err(PgnError::UnclosedComment);
+        }
+      }
+      ';' => while characters.next_if(|&c| c != '\n').is_some() {},
+      '(' => depth += 1,
+      ')' if depth > 0 => depth -= 1,
+      ')' => return Err(PgnError::UnbalancedVariation),
+      _ if character.is_whitespace() => {}
+      _ => {
+        let mut symbol = String::from(character);
+        while let Some(next) = characters.next_if(|c| !c.is_whitespace() && !"[]{}();".contains(*c))
+        {
+          symbol.push(next);
+        }
+        if depth > 0 {
+          continue;
+        }
+        match symbol.as_str() {
+          "1-0" | "0-1" | "1/2-1/2" | "*" => {
+            result = symbol;
+            break;
+          }
+          _ if symbol.starts_with('$') => {}
+          _ => {
+            // move numbers like `12.` or `12...` can be glued to the move
+            let san = match symbol.rfind('.') {
+              Some(idx) => &symbol[idx + 1..],
+              None => symbol.as_str(),
+            };
+            if !san.chars().all(|c| matches!(c, '!' | '?')) {
+              sans.push(san.to_string());
+            }
+          }
+        }
+      }
+    }
+  }
+  if depth > 0 {
+    return Err(PgnError::UnbalancedVariation);
+  }
+
+  let start = tags
+    .iter()
+    .find(|(name, _)| name == "FEN")
+    .map(|(_, fen)| Position::from_fen(fen).map_err(PgnError::InvalidFen))
+    .unwrap_or_else(|| Ok(Position::starting_position()))?;
+  let mut position = start.clone();
+  let mut moves = Vec::new();
+  for san in sans {
+    let mv = parse_san(&position, san.as_str()).ok_or(PgnError::IllegalMove {
+      move_number: position.fullmove_number,
+      san,
+    })?;
+    make_move(&mut position, &mv);
+    moves.push(mv);
+  }
+  Ok(Game {
+    tags,
+    start,
+    moves,
+    result,
+  })
+}
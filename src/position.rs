@@ -0,0 +1,274 @@
+use crate::{
+  chess960::get_back_rank,
+  fen::{
+    generate_fen_from_board, get_en_passant_squares, get_king_file, get_outer_rooks,
+    get_possible_castling, is_valid_en_passant, parse_fen, CastlingFiles, CastlingNotation,
+    CastlingRights, FenError, CLASSICAL_CASTLING_FILES,
+  },
+  movegen::{generate_legal_moves, make_move, Move},
+  status::{get_status, is_insufficient_material, Status},
+  validate::{validate_position, Problem},
+};
+
+pub const ROWS: usize = 8;
+pub const COLUMNS: usize = 8;
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Piece {
+  Bishop,
+  King,
+  Knight,
+  Pawn,
+  Queen,
+  Rook,
+  #[default]
+  None,
+}
+
+impl Piece {
+  pub fn from_usize(value: usize) -> Piece {
+    match value {
+      0 => Piece::Bishop,
+      1 => Piece::King,
+      2 => Piece::Knight,
+      3 => Piece::Pawn,
+      4 => Piece::Queen,
+      5 => Piece::Rook,
+      _ => Piece::None,
+    }
+  }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PlayerPiece {
+  pub player: usize,
+  pub piece_idx: Piece,
+}
+
+// everything a FEN string describes, without any of the editor state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+  pub board: [[PlayerPiece; ROWS]; COLUMNS],
+  // 0 for black, 1 for white
+  pub player: usize,
+  pub castle_white: (bool, bool),
+  pub castle_black: (bool, bool),
+  // the files of the castling rooks, indexed by player like `board`
+  pub castling_files: [CastlingFiles; 2],
+  // kings and rooks may castle from any file
+  pub chess960: bool,
+  // (row, column) of the target square, row 0 is the eighth rank
+  pub en_passant: Option<(usize, usize)>,
+  pub halfmove_clock: u32,
+  pub fullmove_number: u32,
+}
+
+impl Default for Position {
+  fn default() -> Position {
+    Position {
+      board: Default::default(),
+      player: 1,
+      castle_white: (false, false),
+      castle_black: (false, false),
+      castling_files: [CLASSICAL_CASTLING_FILES; 2],
+      chess960: false,
+      en_passant: None,
+      halfmove_clock: 0,
+      fullmove_number: 1,
+    }
+  }
+}
+
+impl Position {
+  pub fn new() -> Position {
+    Default::default()
+  }
+
+  pub fn starting_position() -> Position {
+    let mut position = Position::new();
+    position.set_starting_position();
+    position
+  }
+
+  pub fn from_fen(fen: &str) -> Result<Position, FenError> {
+    parse_fen(fen)
+  }
+
+  pub fn to_fen(&self) -> String {
+    self.to_fen_with(CastlingNotation::XFen)
+  }
+
+  pub fn to_fen_with(&self, notation: CastlingNotation) -> String {
+    generate_fen_from_board(self, notation)
+  }
+
+  pub fn get_piece(&self, square: (usize, usize)) -> Option<(usize, Piece)> {
+    let piece = self.board[square.0][square.1];
+    match piece.piece_idx {
+      Piece::None => None,
+      piece_idx => Some((piece.player, piece_idx)),
+    }
+  }
+
+  pub fn put_piece(&mut self, square: (usize, usize), player: usize, piece: Piece) {
+    self.board[square.0][square.1] = PlayerPiece {
+      player,
+      piece_idx: piece,
+    };
+  }
+
+  pub fn remove_piece(&mut self, square: (usize, usize)) {
+    self.board[square.0][square.1] = Default::default();
+  }
+
+  pub fn clear_board(&mut self) {
+    self.board = Default::default();
+    self.en_passant = None;
+  }
+
+  pub fn set_starting_position(&mut self) {
+    self.set_back_ranks([
+      Piece::Rook,
+      Piece::Knight,
+      Piece::Bishop,
+      Piece::Queen,
+      Piece::King,
+      Piece::Bishop,
+      Piece::Knight,
+      Piece::Rook,
+    ]);
+  }
+
+  // the Chess960 start position with the number, see `get_back_rank`
+  pub fn set_chess960_position(&mut self, number: u32) {
+    let pieces = get_back_rank(number);
+    self.set_back_ranks(pieces);
+    let mut rooks = (0..ROWS).filter(|&j| pieces[j] == Piece::Rook);
+    let queenside = rooks.next().unwrap();
+    let kingside = rooks.next().unwrap();
+    self.castling_files = [(kingside, queenside); 2];
+    self.chess960 = true;
+  }
+
+  // a start position with the pieces on the first rows mirrored for black
+  fn set_back_ranks(&mut self, pieces: [Piece; ROWS]) {
+    *self = Position::new();
+    for (j, piece) in pieces.into_iter().enumerate() {
+      self.put_piece((0, j), 0, piece);
+      self.put_piece((1, j), 0, Piece::Pawn);
+      self.put_piece((COLUMNS - 2, j), 1, Piece::Pawn);
+      self.put_piece((COLUMNS - 1, j), 1, piece);
+    }
+    self.castle_white = (true, true);
+    self.castle_black = (true, true);
+  }
+
+  // the castling rights the king and rook squares allow, white first
+  pub fn get_possible_castling(&self) -> (CastlingRights, CastlingRights) {
+    (
+      get_possible_castling(&self.board, 1, self.castling_files[1], self.chess960),
+      get_possible_castling(&self.board, 0, self.castling_files[0], self.chess960),
+    )
+  }
+
+  // leaving Chess960 puts the castling rooks back in the corners
+  pub fn set_chess960(&mut self, chess960: bool) {
+    self.chess960 = chess960;
+    if !chess960 {
+      self.castling_files = [CLASSICAL_CASTLING_FILES; 2];
+    }
+    self.update_castling_files();
+  }
+
+  // in Chess960 a side whose castling rook is gone from its file castles
+  // with the outermost rook on that side of the king instead, like an
+  // edited board would be read as X-FEN
+  pub fn update_castling_files(&mut self) {
+    if !self.chess960 {
+      return;
+    }
+    for player in 0..2 {
+      let Some(king) = get_king_file(&self.board, player) else {
+        continue;
+      };
+      let outer = get_outer_rooks(&self.board, player);
+      let possible = get_possible_castling(&self.board, player, self.castling_files[player], true);
+      let files = &mut self.castling_files[player];
+      match outer.0 {
+        Some(rook) if !possible.0 && rook > king => files.0 = rook,
+        _ => {}
+      }
+      match outer.1 {
+        Some(rook) if !possible.1 && rook < king => files.1 = rook,
+        _ => {}
+      }
+    }
+  }
+
+  // removes the castling rights of kings and rooks that left their
+  // starting squares
+  pub fn restrict_castling(&mut self) {
+    let (white, black) = self.get_possible_castling();
+    self.castle_white = (
+      self.castle_white.0 && white.0,
+      self.castle_white.1 && white.1,
+    );
+    self.castle_black = (
+      self.castle_black.0 && black.0,
+      self.castle_black.1 && black.1,
+    );
+  }
+
+  // automatic castling rights, a right turns on when the edit made it
+  // possible, given the rights possible before the edit, and off once it
+  // is impossible, so rights turned off by hand stay off
+  pub fn update_auto_castling(&mut self, before: (CastlingRights, CastlingRights)) {
+    let (white, black) = self.get_possible_castling();
+    let (before_white, before_black) = before;
+    self.castle_white.0 |= white.0 && !before_white.0;
+    self.castle_white.1 |= white.1 && !before_white.1;
+    self.castle_black.0 |= black.0 && !before_black.0;
+    self.castle_black.1 |= black.1 && !before_black.1;
+    self.restrict_castling();
+  }
+
+  pub fn get_en_passant_squares(&self) -> Vec<(usize, usize)> {
+    get_en_passant_squares(&self.board, self.player)
+  }
+
+  // drops the en passant square once an edit or a change of the side to
+  // move makes it impossible
+  pub fn update_en_passant(&mut self) {
+    if let Some(square) = self.en_passant {
+      if !is_valid_en_passant(&self.board, self.player, square) {
+        self.en_passant = None;
+      }
+    }
+  }
+
+  pub fn get_legal_moves(&self) -> Vec<Move> {
+    generate_legal_moves(self)
+  }
+
+  pub fn make_move(&mut self, mv: &Move) {
+    make_move(self, mv);
+  }
+
+  pub fn get_status(&self) -> Status {
+    get_status(self)
+  }
+
+  pub fn is_insufficient_material(&self) -> bool {
+    is_insufficient_material(&self.board)
+  }
+
+  pub fn validate(&self) -> Vec<Problem> {
+    validate_position(
+      &self.board,
+      self.player,
+      self.castle_white,
+      self.castle_black,
+      self.get_possible_castling(),
+    )
+  }
+}
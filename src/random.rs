@@ -0,0 +1,28 @@
+use std::{
+  collections::hash_map::RandomState,
+  hash::{BuildHasher, Hasher},
+};
+
+// xorshift, plenty for picking squares and start positions, there is no
+// random number generator in std, but every RandomState gets its own
+// hash keys, seeded randomly once per thread
+pub struct Random(u64);
+
+impl Random {
+  pub fn new() -> Random {
+    Random(RandomState::new().build_hasher().finish() | 1)
+  }
+
+  pub fn get_below(&mut self, bound: usize) -> usize {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    (self.0 % bound as u64) as usize
+  }
+}
+
+impl Default for Random {
+  fn default() -> Random {
+    Random::new()
+  }
+}
@@ -0,0 +1,221 @@
+use crate::{
+  attack::is_in_check,
+  fen::get_square_name,
+  movegen::{generate_legal_moves, get_castling_rook, make_move, Move, MoveKind},
+  position::{Piece, Position, COLUMNS},
+};
+
+fn get_letter_for_piece(piece: Piece) -> Option<char> {
+  match piece {
+    Piece::King => Some('K'),
+    Piece::Queen => Some('Q'),
+    Piece::Rook => Some('R'),
+    Piece::Bishop => Some('B'),
+    Piece::Knight => Some('N'),
+    _ => None,
+  }
+}
+
+fn get_piece_for_letter(letter: char) -> Option<Piece> {
+  match letter {
+    'K' => Some(Piece::King),
+    'Q' => Some(Piece::Queen),
+    'R' => Some(Piece::Rook),
+    'B' => Some(Piece::Bishop),
+    'N' => Some(Piece::Knight),
+    _ => None,
+  }
+}
+
+fn get_file(character: char) -> Option<usize> {
+  match character {
+    'a'..='h' => Some(character as usize - 'a' as usize),
+    _ => None,
+  }
+}
+
+fn get_row(character: char) -> Option<usize> {
+  match character {
+    '1'..='8' => Some(COLUMNS - character.to_digit(10).unwrap() as usize),
+    _ => None,
+  }
+}
+
+// the standard algebraic notation of a legal move, with the check and
+// mate markers
+pub fn get_san(position: &Position, mv: &Move) -> String {
+  let (_, piece) = position
+    .get_piece(mv.from)
+    .expect("a move needs a piece on its starting square");
+  let mut san = String::new();
+  match mv.kind {
+    MoveKind::Castle { kingside } => san += if kingside { "O-O" } else { "O-O-O" },
+    _ => {
+      let is_capture = position.get_piece(mv.to).is_some() || mv.kind == MoveKind::EnPassant;
+      let from_name = get_square_name(mv.from);
+      match get_letter_for_piece(piece) {
+        Some(letter) => {
+          san.push(letter);
+          // other pieces of the same kind that can reach the square
+          let others: Vec<Move> = generate_legal_moves(position)
+            .into_iter()
+            .filter(|other| {
+              other.to == mv.to
+                && other.from != mv.from
+                && !matches!(other.kind, MoveKind::Castle { .. })
+                && position.get_piece(other.from) == Some((position.player, piece))
+            })
+            .collect();
+          if !others.is_empty() {
+            let same_file = others.iter().any(|other| other.from.1 == mv.from.1);
+            let same_row = others.iter().any(|other| other.from.0 == mv.from.0);
+            if !same_file {
+              san.push_str(&from_name[..1]);
+            } else if !same_row {
+              san.push_str(&from_name[1..]);
+            } else {
+              san += from_name.as_str();
+            }
+          }
+        }
+        None if is_capture => san.push_str(&from_name[..1]),
+        None => {}
+      }
+      if is_capture {
+        san.push('x');
+      }
+      san += get_square_name(mv.to).as_str();
+      if let Some(letter) = mv.promotion.and_then(get_letter_for_piece) {
+        san.push('=');
+        san.push(letter);
+      }
+    }
+  }
+  let mut next = position.clone();
+  make_move(&mut next, mv);
+  if is_in_check(&next.board, next.player) {
+    san.push(if generate_legal_moves(&next).is_empty() {
+      '#'
+    } else {
+      '+'
+    });
+  }
+  san
+}
+
+// finds the legal move a SAN string describes, also accepting the
+// variations seen in the wild: zeros for castling, a missing `=` before
+// the promotion piece and long algebraic moves like `e2-e4`
+pub fn parse_san(position: &Position, san: &str) -> Option<Move> {
+  let san = san.trim_end_matches(['+', '#', '!', '?']);
+  let moves = generate_legal_moves(position);
+  let castle = match san {
+    "O-O" | "0-0" => Some(true),
+    "O-O-O" | "0-0-0" => Some(false),
+    _ => None,
+  };
+  if let Some(kingside) = castle {
+    return moves
+      .into_iter()
+      .find(|mv| mv.kind == MoveKind::Castle { kingside });
+  }
+
+  let mut characters: Vec<char> = san
+    .chars()
+    .filter(|c| !matches!(c, 'x' | ':' | '-'))
+    .collect();
+  let promotion = match characters.last().copied().and_then(get_piece_for_letter) {
+    Some(piece) if characters.len() > 2 => {
+      characters.pop();
+      if characters.last() == Some(&'=') {
+        characters.pop();
+      }
+      Some(piece)
+    }
+    _ => None,
+  };
+  if characters.len() < 2 {
+    return None;
+  }
+  let to = (
+    get_row(characters.pop().unwrap())?,
+    get_file(characters.pop().unwrap())?,
+  );
+  let piece = match characters.first().copied().and_then(get_piece_for_letter) {
+    Some(piece) => {
+      characters.remove(0);
+      piece
+    }
+    None => Piece::Pawn,
+  };
+  let (mut file, mut row) = (None, None);
+  for character in characters {
+    match (get_file(character), get_row(character)) {
+      (Some(j), _) => file = Some(j),
+      (_, Some(i)) => row = Some(i),
+      _ => return None,
+    }
+  }
+
+  let mut candidates = moves.into_iter().filter(|mv| {
+    mv.to == to
+      && mv.promotion == promotion
+      && !matches!(mv.kind, MoveKind::Castle { .. })
+      && position.get_piece(mv.from) == Some((position.player, piece))
+      && file.is_none_or(|j| mv.from.1 == j)
+      && row.is_none_or(|i| mv.from.0 == i)
+  });
+  let mv = candidates.next()?;
+  // an ambiguous move matches more than one
+  match candidates.next() {
+    Some(_) => None,
+    None => Some(mv),
+  }
+}
+
+// in Chess960 engines write castling as the king taking its own rook
+pub fn get_uci_move(position: &Position, mv: &Move) -> String {
+  let to = match get_castling_rook(position, mv) {
+    Some(rook) if position.chess960 => rook,
+    _ => mv.to,
+  };
+  let mut uci = get_square_name(mv.from) + get_square_name(to).as_str();
+  if let Some(letter) = mv.promotion.and_then(get_letter_for_piece) {
+    uci.push(letter.to_ascii_lowercase());
+  }
+  uci
+}
+
+pub fn parse_uci_move(position: &Position, uci: &str) -> Option<Move> {
+  position
+    .get_legal_moves()
+    .into_iter()
+    .find(|mv| get_uci_move(position, mv) == uci)
+}
+
+// reads a line of moves typed in SAN or in coordinates like `e2e4`, move
+// numbers may be left out, the moves stop at the first token that is
+// not a legal move, the error names that token
+pub fn parse_move_line(position: &Position, line: &str) -> (Vec<Move>, Option<String>) {
+  let mut position = position.clone();
+  let mut moves = Vec::new();
+  for token in line.split_whitespace() {
+    let text = match token.rfind('.') {
+      Some(idx) => &token[idx + 1..],
+      None => token,
+    };
+    if text.is_empty() {
+      continue;
+    }
+    let mv = parse_san(&position, text)
+      .or_else(|| parse_uci_move(&position, text.replace('-', "").to_lowercase().as_str()));
+    match mv {
+      Some(mv) => {
+        make_move(&mut position, &mv);
+        moves.push(mv);
+      }
+      None => return (moves, Some(format!("{} is not a legal move", token))),
+    }
+  }
+  (moves, None)
+}
@@ -0,0 +1,47 @@
+use crate::{
+  attack::is_in_check,
+  movegen::generate_legal_moves,
+  position::{Piece, PlayerPiece, Position, COLUMNS, ROWS},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+  Normal,
+  Check,
+  Checkmate,
+  Stalemate,
+}
+
+pub fn get_status(position: &Position) -> Status {
+  let in_check = is_in_check(&position.board, position.player);
+  let has_moves = !generate_legal_moves(position).is_empty();
+  match (in_check, has_moves) {
+    (true, true) => Status::Check,
+    (true, false) => Status::Checkmate,
+    (false, true) => Status::Normal,
+    (false, false) => Status::Stalemate,
+  }
+}
+
+// neither side can ever mate: bare kings, a single minor piece, or only
+// bishops that all stand on squares of the same colour
+pub fn is_insufficient_material(board: &[[PlayerPiece; ROWS]; COLUMNS]) -> bool {
+  let mut knights = 0;
+  let mut bishop_colours = [false, false];
+  for (i, row) in board.iter().enumerate() {
+    for (j, piece) in row.iter().enumerate() {
+      match piece.piece_idx {
+        Piece::King | Piece::None => {}
+        Piece::Knight => knights += 1,
+        Piece::Bishop => bishop_colours[(i + j) % 2] = true,
+        Piece::Pawn | Piece::Rook | Piece::Queen => return false,
+      }
+    }
+  }
+  let has_bishops = bishop_colours[0] || bishop_colours[1];
+  match knights {
+    0 => !(bishop_colours[0] && bishop_colours[1]),
+    1 => !has_bishops,
+    _ => false,
+  }
+}
@@ -0,0 +1,94 @@
+use crate::position::{Piece, Position, COLUMNS, ROWS};
+
+// castling rights and en passant squares a transform breaks are dropped,
+// the rest move along with the pieces
+fn tidy(position: &mut Position) {
+  position.update_castling_files();
+  position.restrict_castling();
+  position.update_en_passant();
+}
+
+// swaps the a and h files, kingside castling becomes queenside castling
+pub fn mirror_files(position: &Position) -> Position {
+  let mut mirrored = position.clone();
+  for (i, row) in position.board.iter().enumerate() {
+    for (j, &piece) in row.iter().enumerate() {
+      mirrored.board[i][ROWS - 1 - j] = piece;
+    }
+  }
+  mirrored.castle_white = (position.castle_white.1, position.castle_white.0);
+  mirrored.castle_black = (position.castle_black.1, position.castle_black.0);
+  for (files, &(kingside, queenside)) in mirrored
+    .castling_files
+    .iter_mut()
+    .zip(position.castling_files.iter())
+  {
+    *files = (ROWS - 1 - queenside, ROWS - 1 - kingside);
+  }
+  mirrored.en_passant = position.en_passant.map(|(i, j)| (i, ROWS - 1 - j));
+  tidy(&mut mirrored);
+  mirrored
+}
+
+// the same position seen from the other side, the ranks are flipped, the
+// pieces change colour and the other side moves
+pub fn swap_colours(position: &Position) -> Position {
+  let mut swapped = position.clone();
+  for (i, row) in position.board.iter().enumerate() {
+    for (j, &piece) in row.iter().enumerate() {
+      let square = &mut swapped.board[COLUMNS - 1 - i][j];
+      *square = piece;
+      square.player = 1 - piece.player;
+    }
+  }
+  swapped.player = 1 - position.player;
+  swapped.castle_white = position.castle_black;
+  swapped.castle_black = position.castle_white;
+  swapped.castling_files = [position.castling_files[1], position.castling_files[0]];
+  swapped.en_passant = position.en_passant.map(|(i, j)| (COLUMNS - 1 - i, j));
+  tidy(&mut swapped);
+  swapped
+}
+
+fn shift_square(square: (usize, usize), rows: isize, columns: isize) -> Option<(usize, usize)> {
+  let i = square.0.checked_add_signed(rows).filter(|&i| i < COLUMNS)?;
+  let j = square.1.checked_add_signed(columns).filter(|&j| j < ROWS)?;
+  Some((i, j))
+}
+
+// moves every piece by the given rows and columns, rows count down from
+// the eighth rank, none when a piece would leave the board or a pawn would
+// land on a back rank
+pub fn shift_pieces(position: &Position, rows: isize, columns: isize) -> Option<Position> {
+  let mut shifted = position.clone();
+  shifted.board = Default::default();
+  for (i, row) in position.board.iter().enumerate() {
+    for (j, &piece) in row.iter().enumerate() {
+      if position.get_piece((i, j)).is_some() {
+        let (i, j) = shift_square((i, j), rows, columns)?;
+        if piece.piece_idx == Piece::Pawn && (i == 0 || i == ROWS - 1) {
+          return None;
+        }
+        shifted.board[i][j] = piece;
+      }
+    }
+  }
+  // the castling rooks only keep their files in Chess960, classical
+  // castling needs the corners anyway
+  if position.chess960 {
+    for files in shifted.castling_files.iter_mut() {
+      let (kingside, queenside) = *files;
+      if let (Some((_, kingside)), Some((_, queenside))) = (
+        shift_square((0, kingside), 0, columns),
+        shift_square((0, queenside), 0, columns),
+      ) {
+        *files = (kingside, queenside);
+      }
+    }
+  }
+  shifted.en_passant = position
+    .en_passant
+    .and_then(|square| shift_square(square, rows, columns));
+  tidy(&mut shifted);
+  Some(shifted)
+}
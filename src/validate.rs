@@ -0,0 +1,186 @@
+use crate::{
+  attack::{find_king, get_attackers, is_in_check},
+  fen::{get_square_name, CastlingRights},
+  position::{Piece, PlayerPiece, COLUMNS, ROWS},
+};
+use std::fmt;
+
+const MAX_PIECES: usize = 16;
+const MAX_PAWNS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+  KingCount { player: usize, count: usize },
+  PawnOnBackRank { square: (usize, usize) },
+  TooManyPieces { player: usize, count: usize },
+  TooManyPawns { player: usize, count: usize },
+  // more promoted pieces than the missing pawns could have become
+  TooManyPromotions { player: usize, count: usize },
+  OpponentInCheck,
+  ImpossibleCheck { checkers: Vec<(usize, usize)> },
+  CastlingRights { player: usize, kingside: bool },
+}
+
+fn get_player_name(player: usize) -> &'static str {
+  if player == 1 {
+    "White"
+  } else {
+    "Black"
+  }
+}
+
+impl fmt::Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Problem::KingCount { player, count } => {
+        write!(f, "{} has {} kings", get_player_name(*player), count)
+      }
+      Problem::PawnOnBackRank { square } => {
+        write!(f, "Pawn on the back rank at {}", get_square_name(*square))
+      }
+      Problem::TooManyPieces { player, count } => {
+        write!(f, "{} has {} pieces", get_player_name(*player), count)
+      }
+      Problem::TooManyPawns { player, count } => {
+        write!(f, "{} has {} pawns", get_player_name(*player), count)
+      }
+      Problem::TooManyPromotions { player, count } => write!(
+        f,
+        "{} has too many promoted pieces ({} extra)",
+        get_player_name(*player),
+        count
+      ),
+      Problem::OpponentInCheck => write!(f, "The side not to move is in check"),
+      Problem::ImpossibleCheck { checkers } => write!(
+        f,
+        "Impossible check from {}",
+        checkers
+          .iter()
+          .map(|square| get_square_name(*square))
+          .collect::<Vec<String>>()
+          .join(", ")
+      ),
+      Problem::CastlingRights { player, kingside } => write!(
+        f,
+        "{} cannot castle {}",
+        get_player_name(*player),
+        if *kingside { "kingside" } else { "queenside" }
+      ),
+    }
+  }
+}
+
+fn count_pieces(board: &[[PlayerPiece; ROWS]; COLUMNS], player: usize, piece: Piece) -> usize {
+  board
+    .iter()
+    .flatten()
+    .filter(|curr| curr.player == player && curr.piece_idx == piece)
+    .count()
+}
+
+fn validate_material(board: &[[PlayerPiece; ROWS]; COLUMNS], player: usize) -> Vec<Problem> {
+  let mut problems = Vec::new();
+  let kings = count_pieces(board, player, Piece::King);
+  if kings != 1 {
+    problems.push(Problem::KingCount {
+      player,
+      count: kings,
+    });
+  }
+  let total = board
+    .iter()
+    .flatten()
+    .filter(|curr| curr.player == player && curr.piece_idx != Piece::None)
+    .count();
+  if total > MAX_PIECES {
+    problems.push(Problem::TooManyPieces {
+      player,
+      count: total,
+    });
+  }
+  let pawns = count_pieces(board, player, Piece::Pawn);
+  if pawns > MAX_PAWNS {
+    problems.push(Problem::TooManyPawns {
+      player,
+      count: pawns,
+    });
+  }
+  let promoted: usize = [
+    (Piece::Queen, 1),
+    (Piece::Rook, 2),
+    (Piece::Bishop, 2),
+    (Piece::Knight, 2),
+  ]
+  .iter()
+  .map(|(piece, initial)| count_pieces(board, player, *piece).saturating_sub(*initial))
+  .sum();
+  if promoted > MAX_PAWNS.saturating_sub(pawns) {
+    problems.push(Problem::TooManyPromotions {
+      player,
+      count: promoted - MAX_PAWNS.saturating_sub(pawns),
+    });
+  }
+  problems
+}
+
+// a double check always includes the line piece a move uncovered, so two
+// checkers without a bishop, rook or queen, or more than two checkers,
+// cannot come from a real game
+fn is_possible_check(board: &[[PlayerPiece; ROWS]; COLUMNS], checkers: &[(usize, usize)]) -> bool {
+  match checkers.len() {
+    0 | 1 => true,
+    2 => checkers.iter().any(|&(i, j)| {
+      matches!(
+        board[i][j].piece_idx,
+        Piece::Bishop | Piece::Rook | Piece::Queen
+      )
+    }),
+    _ => false,
+  }
+}
+
+fn validate_castling(
+  player: usize,
+  castle: (bool, bool),
+  possible: CastlingRights,
+) -> Vec<Problem> {
+  let mut problems = Vec::new();
+  for (allowed, possible, kingside) in [(castle.0, possible.0, true), (castle.1, possible.1, false)]
+  {
+    if allowed && !possible {
+      problems.push(Problem::CastlingRights { player, kingside });
+    }
+  }
+  problems
+}
+
+pub fn validate_position(
+  board: &[[PlayerPiece; ROWS]; COLUMNS],
+  player: usize,
+  castle_white: (bool, bool),
+  castle_black: (bool, bool),
+  // white first, like `Position::get_possible_castling`
+  possible_castling: (CastlingRights, CastlingRights),
+) -> Vec<Problem> {
+  let mut problems = validate_material(board, 1);
+  problems.extend(validate_material(board, 0));
+  for i in [0, COLUMNS - 1] {
+    for (j, piece) in board[i].iter().enumerate() {
+      if piece.piece_idx == Piece::Pawn {
+        problems.push(Problem::PawnOnBackRank { square: (i, j) });
+      }
+    }
+  }
+  if is_in_check(board, 1 - player) {
+    problems.push(Problem::OpponentInCheck);
+  }
+  if let Some(king) = find_king(board, player) {
+    let checkers = get_attackers(board, king, 1 - player);
+    if !is_possible_check(board, &checkers) {
+      problems.push(Problem::ImpossibleCheck { checkers });
+    }
+  }
+  problems.extend(validate_castling(1, castle_white, possible_castling.0));
+  problems.extend(validate_castling(0, castle_black, possible_castling.1));
+  problems
+}
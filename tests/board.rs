@@ -0,0 +1,256 @@
+#![cfg(feature = "gui")]
+
+use allegro::{Event, Flag, KeyCode, KeyModifier};
+use board_editor::{board::Board, position::Piece};
+use std::ptr;
+
+fn drag(board: &mut Board, from: (f32, f32), to: (f32, f32)) {
+  board.event_listener(&Event::MouseButtonDown {
+    source: ptr::null_mut(),
+    timestamp: 0.0,
+    x: from.0 as i32,
+    y: from.1 as i32,
+    z: 0,
+    w: 0,
+    button: 1,
+    display: ptr::null_mut(),
+  });
+  board.event_listener(&Event::MouseButtonUp {
+    source: ptr::null_mut(),
+    timestamp: 0.0,
+    x: to.0 as i32,
+    y: to.1 as i32,
+    z: 0,
+    w: 0,
+    button: 1,
+    display: ptr::null_mut(),
+  });
+}
+
+fn click(board: &mut Board, point: (f32, f32)) {
+  drag(board, point, point);
+}
+
+fn click_square(board: &mut Board, square: (usize, usize)) {
+  let center = board.get_square_center(square);
+  click(board, center);
+}
+
+fn click_shelf(board: &mut Board, side: usize, cell: usize) {
+  let center = board.get_shelf_center(side, cell);
+  click(board, center);
+}
+
+fn drag_squares(board: &mut Board, from: (usize, usize), to: (usize, usize)) {
+  let (from, to) = (board.get_square_center(from), board.get_square_center(to));
+  drag(board, from, to);
+}
+
+fn type_key(board: &mut Board, keycode: KeyCode, unichar: char) -> bool {
+  board.event_listener(&Event::KeyChar {
+    source: ptr::null_mut(),
+    timestamp: 0.0,
+    keycode,
+    display: ptr::null_mut(),
+    unichar,
+    repeat: false,
+    modifiers: KeyModifier::zero(),
+  })
+}
+
+fn press_key(board: &mut Board, keycode: KeyCode) -> bool {
+  board.event_listener(&Event::KeyDown {
+    source: ptr::null_mut(),
+    timestamp: 0.0,
+    keycode,
+    display: ptr::null_mut(),
+  })
+}
+
+fn type_text(board: &mut Board, text: &str) {
+  for character in text.chars() {
+    type_key(board, KeyCode::Unknown, character);
+  }
+}
+
+#[test]
+fn flipping_the_board_does_not_change_the_fen() {
+  let mut board = Board::new();
+  board.set_starting_position();
+  for player in 0..2 {
+    board.get_position_mut().player = player;
+    let fen = board.get_fen();
+    board.flip_board();
+    assert_eq!(board.get_fen(), fen);
+    board.flip_board();
+    assert_eq!(board.get_fen(), fen);
+  }
+}
+
+#[test]
+fn side_to_move_comes_from_the_position() {
+  let mut board = Board::new();
+  board.set_starting_position();
+  assert_eq!(
+    board.get_fen(),
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+  );
+  board.flip_board();
+  board.get_position_mut().player = 0;
+  assert_eq!(
+    board.get_fen(),
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+  );
+}
+
+#[test]
+fn pieces_are_placed_with_the_keyboard() {
+  let mut board = Board::new();
+  board.clear_board();
+  // keys only reach the board after a click on it, here on e2
+  assert!(!type_key(&mut board, KeyCode::Q, 'Q'));
+  click_square(&mut board, (6, 4));
+  type_text(&mut board, "Q");
+  type_key(&mut board, KeyCode::Enter, '\r');
+  type_text(&mut board, "b7b");
+  type_key(&mut board, KeyCode::Enter, '\r');
+  type_text(&mut board, "ne4K");
+  type_key(&mut board, KeyCode::Right, '\0');
+  type_key(&mut board, KeyCode::Enter, '\r');
+  type_key(&mut board, KeyCode::Left, '\0');
+  type_key(&mut board, KeyCode::Delete, '\u{7f}');
+  assert_eq!(board.get_fen(), "8/1b6/8/8/5K2/8/4Q3/8 w - - 0 1");
+  click(&mut board, (0.0, 0.0));
+  assert!(!type_key(&mut board, KeyCode::Q, 'q'));
+}
+
+#[test]
+fn the_cursor_stays_on_the_board() {
+  let mut board = Board::new();
+  board.clear_board();
+  // the bottom right corner is h1 for white and a8 for black
+  for (square, fen) in [
+    ((7, 7), "8/8/8/8/8/8/8/7R w - - 0 1"),
+    ((0, 0), "R7/8/8/8/8/8/8/8 w - - 0 1"),
+  ] {
+    click_square(&mut board, square);
+    type_key(&mut board, KeyCode::Down, '\0');
+    type_key(&mut board, KeyCode::Right, '\0');
+    type_text(&mut board, "R");
+    type_key(&mut board, KeyCode::Enter, '\r');
+    assert_eq!(board.get_fen(), fen);
+    board.clear_board();
+    board.flip_board();
+  }
+}
+
+#[test]
+fn a_brush_stamps_its_piece() {
+  let mut board = Board::new();
+  board.clear_board();
+  let pawn = Piece::Pawn as usize + 1;
+  // the white pawn on the bottom shelf, then a2, b2, c2 and a2 again
+  click_shelf(&mut board, 1, pawn);
+  for j in [0, 1, 2, 0] {
+    click_square(&mut board, (6, j));
+  }
+  assert_eq!(board.get_fen(), "8/8/8/8/8/8/1PP5/8 w - - 0 1");
+  // the pointer puts clicks back to moving pieces
+  click_shelf(&mut board, 1, 0);
+  click_square(&mut board, (6, 1));
+  assert_eq!(board.get_fen(), "8/8/8/8/8/8/1PP5/8 w - - 0 1");
+}
+
+#[test]
+fn dragging_from_the_shelf_does_not_arm_the_brush() {
+  let mut board = Board::new();
+  board.clear_board();
+  let knight = board.get_shelf_center(0, Piece::Knight as usize + 1);
+  let b8 = board.get_square_center((0, 1));
+  drag(&mut board, knight, b8);
+  assert_eq!(board.get_fen(), "1n6/8/8/8/8/8/8/8 w - - 0 1");
+  click_square(&mut board, (0, 6));
+  assert_eq!(board.get_fen(), "1n6/8/8/8/8/8/8/8 w - - 0 1");
+  // nor does a press on one shelf cell and a release on another
+  let bishop = board.get_shelf_center(0, Piece::Bishop as usize + 1);
+  drag(&mut board, knight, bishop);
+  click_square(&mut board, (0, 6));
+  assert_eq!(board.get_fen(), "1n6/8/8/8/8/8/8/8 w - - 0 1");
+}
+
+#[test]
+fn analysis_mode_ignores_the_shelves_and_plays_moves() {
+  let mut board = Board::new();
+  board.set_starting_position();
+  board.set_analysis_mode(true);
+  let fen = board.get_fen();
+  for side in 0..2 {
+    click_shelf(&mut board, side, Piece::Queen as usize + 1);
+  }
+  assert_eq!(board.get_fen(), fen);
+  // e2 to e4
+  drag_squares(&mut board, (6, 4), (4, 4));
+  assert_eq!(
+    board.get_fen(),
+    "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+  );
+}
+
+#[test]
+fn the_move_list_keeps_left_and_right_while_a_game_is_loaded() {
+  let mut board = Board::new();
+  board.clear_board();
+  click_square(&mut board, (6, 4));
+  board.set_game_loaded(true);
+  assert!(!type_key(&mut board, KeyCode::Left, '\0'));
+  assert!(!type_key(&mut board, KeyCode::Right, '\0'));
+  assert!(type_key(&mut board, KeyCode::Up, '\0'));
+  board.set_game_loaded(false);
+  assert!(type_key(&mut board, KeyCode::Left, '\0'));
+}
+
+#[test]
+fn a_typed_piece_does_not_arm_the_brush() {
+  let mut board = Board::new();
+  board.clear_board();
+  click_square(&mut board, (6, 4));
+  type_text(&mut board, "Q");
+  click_square(&mut board, (6, 3));
+  assert_eq!(board.get_fen(), "8/8/8/8/8/8/8/8 w - - 0 1");
+  type_key(&mut board, KeyCode::Enter, '\r');
+  assert_eq!(board.get_fen(), "8/8/8/8/8/8/3Q4/8 w - - 0 1");
+}
+
+// a white pawn on e7 with the kings out of the way
+fn get_promotion_board() -> Board {
+  let mut board = Board::new();
+  board.load_fen("8/4P3/8/8/8/8/8/k6K w - - 0 1").unwrap();
+  board
+}
+
+#[test]
+fn a_pawn_dropped_on_the_back_rank_opens_the_picker() {
+  let mut board = get_promotion_board();
+  drag_squares(&mut board, (1, 4), (0, 4));
+  assert!(board.is_promoting());
+  assert_eq!(board.get_fen(), "8/4P3/8/8/8/8/8/k6K w - - 0 1");
+}
+
+#[test]
+fn the_picked_piece_is_placed() {
+  let mut board = get_promotion_board();
+  drag_squares(&mut board, (1, 4), (0, 4));
+  // queen, rook, bishop and knight run down the file from e8
+  click_square(&mut board, (2, 4));
+  assert!(!board.is_promoting());
+  assert_eq!(board.get_fen(), "4B3/8/8/8/8/8/8/k6K w - - 0 1");
+}
+
+#[test]
+fn escape_puts_the_pawn_back() {
+  let mut board = get_promotion_board();
+  drag_squares(&mut board, (1, 4), (0, 4));
+  assert!(press_key(&mut board, KeyCode::Escape));
+  assert!(!board.is_promoting());
+  assert_eq!(board.get_fen(), "8/4P3/8/8/8/8/8/k6K w - - 0 1");
+}
@@ -0,0 +1,82 @@
+use board_editor::{
+  chess960::{get_back_rank, CLASSICAL_NUMBER, POSITION_COUNT},
+  fen::CastlingNotation,
+  position::{Piece, Position},
+  san::{get_uci_move, parse_san},
+};
+
+#[test]
+fn start_positions_are_numbered() {
+  let classical = Position::starting_position();
+  let mut position = Position::new();
+  position.set_chess960_position(CLASSICAL_NUMBER);
+  assert_eq!(position.to_fen(), classical.to_fen());
+  assert_eq!(
+    get_back_rank(0),
+    [
+      Piece::Bishop,
+      Piece::Bishop,
+      Piece::Queen,
+      Piece::Knight,
+      Piece::Knight,
+      Piece::Rook,
+      Piece::King,
+      Piece::Rook
+    ]
+  );
+  for number in 0..960 {
+    let pieces = get_back_rank(number);
+    let files = |piece: Piece| -> Vec<usize> { (0..8).filter(|&j| pieces[j] == piece).collect() };
+    let (bishops, rooks, king) = (files(Piece::Bishop), files(Piece::Rook), files(Piece::King));
+    assert_ne!(bishops[0] % 2, bishops[1] % 2, "position {}", number);
+    assert!(
+      rooks[0] < king[0] && king[0] < rooks[1],
+      "position {}",
+      number
+    );
+  }
+}
+
+#[test]
+fn castling_rights_use_rook_files() {
+  let mut position = Position::new();
+  // rooks on b and f, neither in a corner
+  position.set_chess960_position(331);
+  let fen = position.to_fen();
+  assert!(fen.starts_with("nrqkbrnb/"), "{}", fen);
+  assert!(fen.contains(" w KQkq "));
+  assert!(position
+    .to_fen_with(CastlingNotation::Shredder)
+    .contains(" w FBfb "));
+  assert_eq!(Position::from_fen(&fen).unwrap(), position);
+
+  // an inner rook needs its file in X-FEN
+  let position = Position::from_fen("4k3/8/8/8/8/8/8/R1K2R1R w F - 0 1").unwrap();
+  assert!(position.chess960);
+  assert!(position.to_fen().contains(" w F "));
+  let castle = parse_san(&position, "O-O").unwrap();
+  assert_eq!(get_uci_move(&position, &castle), "c1f1");
+  let mut next = position.clone();
+  next.make_move(&castle);
+  assert_eq!(next.to_fen(), "4k3/8/8/8/8/8/8/R4RKR b - - 1 1");
+}
+
+#[test]
+fn kings_on_the_e_file_castle_with_the_corners() {
+  // a stale right is dropped rather than read as Chess960
+  let position = Position::from_fen("4k3/8/8/8/8/8/8/4K1R1 w K - 0 1").unwrap();
+  assert!(!position.chess960);
+  assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/4K1R1 w - - 0 1");
+  // a Chess960 start with the king on e writes inner rooks as files
+  let number = (0..POSITION_COUNT)
+    .find(|&number| {
+      let pieces = get_back_rank(number);
+      pieces[4] == Piece::King && pieces[7] != Piece::Rook
+    })
+    .unwrap();
+  let mut position = Position::new();
+  position.set_chess960_position(number);
+  let fen = position.to_fen();
+  assert!(!fen.contains(" w KQkq "), "{}", fen);
+  assert_eq!(Position::from_fen(&fen).unwrap(), position);
+}
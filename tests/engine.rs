@@ -0,0 +1,85 @@
+#![cfg(unix)]
+
+use board_editor::{
+  engine::{get_pv_san, parse_info, Engine, EngineInfo, Score},
+  position::Position,
+};
+use std::{
+  fs,
+  os::unix::fs::PermissionsExt,
+  path::PathBuf,
+  thread,
+  time::{Duration, Instant},
+};
+
+// answers every search with two info lines and a best move
+const FAKE_ENGINE: &str = r#"#!/bin/sh
+while read -r line; do
+  case "$line" in
+    uci) echo "id name Fake Engine"; echo "uciok" ;;
+    isready) echo "readyok" ;;
+    "position fen "*) echo "info string got $line" ;;
+    go*)
+      echo "info depth 1 seldepth 1 score cp 25 nodes 20 pv e2e4"
+      echo "info depth 2 seldepth 2 score cp 31 nodes 100 pv e2e4 e7e5"
+      echo "bestmove e2e4"
+      ;;
+    quit) exit 0 ;;
+  esac
+done
+"#;
+
+fn write_fake_engine(name: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("{}-{}.sh", name, std::process::id()));
+  fs::write(&path, FAKE_ENGINE).unwrap();
+  fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+  path
+}
+
+#[test]
+fn info_lines_are_parsed() {
+  assert_eq!(
+    parse_info("info depth 12 seldepth 18 multipv 1 score mate -3 nodes 5 pv h7h8q g8h8"),
+    Some(EngineInfo {
+      depth: 12,
+      score: Some(Score::Mate(-3)),
+      pv: vec!["h7h8q".to_string(), "g8h8".to_string()],
+    })
+  );
+  assert_eq!(
+    parse_info("info depth 3 currmove e2e4 currmovenumber 1"),
+    None
+  );
+  assert_eq!(
+    parse_info("info multipv 2 depth 3 score cp 10 pv d2d4"),
+    None
+  );
+  assert_eq!(Score::Centipawns(31).for_white(0).to_string(), "-0.31");
+  assert_eq!(Score::Mate(-3).for_white(0).to_string(), "#3");
+}
+
+#[test]
+fn the_engine_analyses_the_position() {
+  let path = write_fake_engine("fake-engine");
+  let mut engine = Engine::start(path.to_str().unwrap()).unwrap();
+  let position = Position::starting_position();
+  engine.analyse(position.to_fen().as_str(), false).unwrap();
+
+  let started = Instant::now();
+  while engine.get_info().map(|info| info.depth) != Some(2) {
+    assert!(
+      started.elapsed() < Duration::from_secs(5),
+      "no info from the engine"
+    );
+    engine.poll();
+    thread::sleep(Duration::from_millis(10));
+  }
+  let info = engine.get_info().unwrap();
+  assert_eq!(engine.get_name(), "Fake Engine");
+  assert_eq!(info.score, Some(Score::Centipawns(31)));
+  assert_eq!(get_pv_san(&position, &info.pv), "1. e4 e5");
+  assert!(engine.is_running());
+
+  drop(engine);
+  fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,39 @@
+use board_editor::{
+  eval::evaluate,
+  position::{Piece, Position},
+  transform::swap_colours,
+};
+
+const FENS: [&str; 3] = [
+  "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+  "6k1/5ppp/8/8/8/8/1Q3PPP/6K1 w - - 0 1",
+  "4k3/pp6/8/8/3N4/8/8/R3K3 b - - 0 1",
+];
+
+#[test]
+fn the_start_position_is_even() {
+  assert_eq!(evaluate(&Position::starting_position().board), 0);
+}
+
+#[test]
+fn swapping_colours_negates_the_score() {
+  for fen in FENS {
+    let position = Position::from_fen(fen).unwrap();
+    let swapped = swap_colours(&position);
+    assert_eq!(
+      evaluate(&swapped.board),
+      -evaluate(&position.board),
+      "{}",
+      fen
+    );
+  }
+}
+
+#[test]
+fn an_extra_queen_is_a_large_advantage() {
+  let mut position = Position::starting_position();
+  position.put_piece((5, 3), 1, Piece::Queen);
+  assert!(evaluate(&position.board) > 800);
+  position = swap_colours(&position);
+  assert!(evaluate(&position.board) < -800);
+}
@@ -0,0 +1,74 @@
+use board_editor::{
+  generator::{generate_position, parse_material, Material, MaterialError},
+  position::{Piece, Position},
+};
+
+fn count(position: &Position, player: usize, piece: Piece) -> usize {
+  position
+    .board
+    .iter()
+    .flatten()
+    .filter(|curr| curr.player == player && curr.piece_idx == piece)
+    .count()
+}
+
+#[test]
+fn material_is_read_from_letters_and_words() {
+  assert_eq!(
+    parse_material("KRP vs KR"),
+    Ok(Material::Pieces {
+      white: vec![Piece::Rook, Piece::Pawn],
+      black: vec![Piece::Rook],
+    })
+  );
+  assert_eq!(
+    parse_material("black queen vs two white rooks"),
+    Ok(Material::Pieces {
+      white: vec![Piece::Rook, Piece::Rook],
+      black: vec![Piece::Queen],
+    })
+  );
+  assert_eq!(parse_material("5 random pieces"), Ok(Material::Random(5)));
+  assert_eq!(
+    parse_material("white queen vs white rook"),
+    Err(MaterialError::SameColour)
+  );
+  assert_eq!(
+    parse_material("KQ vs dragon"),
+    Err(MaterialError::UnknownWord("dragon".to_string()))
+  );
+  assert_eq!(parse_material("KKR vs K"), Err(MaterialError::TooManyKings));
+  assert_eq!(
+    parse_material("two kings vs rook"),
+    Err(MaterialError::TooManyKings)
+  );
+}
+
+#[test]
+fn generated_positions_are_legal() {
+  let material = parse_material("white queen and three pawns vs two rooks").unwrap();
+  for _ in 0..50 {
+    let position = generate_position(&material, 0).unwrap();
+    assert!(position.validate().is_empty(), "{}", position.to_fen());
+    assert_eq!(position.player, 0);
+    assert_eq!(count(&position, 1, Piece::Queen), 1);
+    assert_eq!(count(&position, 1, Piece::Pawn), 3);
+    assert_eq!(count(&position, 0, Piece::Rook), 2);
+  }
+  let position = generate_position(&Material::Random(12), 1).unwrap();
+  assert!(position.validate().is_empty(), "{}", position.to_fen());
+  // nine pawns can never be legal
+  assert_eq!(
+    parse_material("KPPPPPPPPP vs K"),
+    Err(MaterialError::TooManyPawns(9))
+  );
+  assert_eq!(
+    parse_material("K vs 15 pawns"),
+    Err(MaterialError::TooManyPawns(15))
+  );
+  let material = Material::Pieces {
+    white: vec![Piece::Pawn; 9],
+    black: Vec::new(),
+  };
+  assert_eq!(generate_position(&material, 1), None);
+}
@@ -0,0 +1,72 @@
+use board_editor::{
+  annotation::{AnnotationColor, Annotations},
+  history::{History, MAX_ENTRIES},
+  position::{Piece, Position},
+};
+
+fn with_king(square: (usize, usize)) -> Position {
+  let mut position = Position::new();
+  position.put_piece(square, 1, Piece::King);
+  position
+}
+
+#[test]
+fn edits_are_undone_and_redone() {
+  let empty = Annotations::new();
+  let first = Position::new();
+  let second = with_king((7, 4));
+  let mut highlighted = Annotations::new();
+  highlighted.toggle_highlight((7, 4), AnnotationColor::Green);
+  let mut history = History::new(&first, &empty);
+  assert!(!history.can_undo());
+  assert!(history.record(&second, &empty));
+  assert!(history.record(&second, &highlighted));
+  assert_eq!(history.undo(), Some((second.clone(), empty.clone())));
+  assert_eq!(history.undo(), Some((first.clone(), empty.clone())));
+  assert_eq!(history.undo(), None);
+  assert!(history.can_redo());
+  assert_eq!(history.redo(), Some((second.clone(), empty.clone())));
+  assert_eq!(history.redo(), Some((second, highlighted)));
+  assert_eq!(history.redo(), None);
+}
+
+#[test]
+fn a_new_edit_clears_redo() {
+  let empty = Annotations::new();
+  let mut history = History::new(&Position::new(), &empty);
+  history.record(&with_king((7, 4)), &empty);
+  history.undo();
+  assert!(history.can_redo());
+  history.record(&with_king((7, 3)), &empty);
+  assert!(!history.can_redo());
+  assert_eq!(history.redo(), None);
+}
+
+#[test]
+fn an_unchanged_position_is_not_recorded() {
+  let empty = Annotations::new();
+  let position = with_king((7, 4));
+  let mut history = History::new(&position, &empty);
+  assert!(!history.record(&position, &empty));
+  assert!(!history.can_undo());
+}
+
+#[test]
+fn the_oldest_entry_is_evicted() {
+  let empty = Annotations::new();
+  let mut history = History::new(&Position::new(), &empty);
+  // the history starts on move 1, so these are MAX_ENTRIES + 1 edits
+  for n in 2..=MAX_ENTRIES as u32 + 2 {
+    let mut position = Position::new();
+    position.fullmove_number = n;
+    assert!(history.record(&position, &empty));
+  }
+  let mut undos = 0;
+  let mut oldest = None;
+  while let Some((position, _)) = history.undo() {
+    undos += 1;
+    oldest = Some(position.fullmove_number);
+  }
+  assert_eq!(undos, MAX_ENTRIES);
+  assert_eq!(oldest, Some(2));
+}
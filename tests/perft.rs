@@ -0,0 +1,74 @@
+use board_editor::{movegen::perft, position::Position};
+
+// node counts from https://www.chessprogramming.org/Perft_Results
+fn check_perft(fen: &str, expected: &[u64]) {
+  let position = Position::from_fen(fen).unwrap();
+  for (depth, nodes) in expected.iter().enumerate() {
+    assert_eq!(perft(&position, depth + 1), *nodes, "depth {}", depth + 1);
+  }
+}
+
+#[test]
+fn perft_starting_position() {
+  check_perft(
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    &[20, 400, 8902, 197281],
+  );
+}
+
+#[test]
+fn perft_kiwipete() {
+  check_perft(
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    &[48, 2039, 97862],
+  );
+}
+
+#[test]
+fn perft_position_3() {
+  check_perft(
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    &[14, 191, 2812, 43238],
+  );
+}
+
+#[test]
+fn perft_position_4() {
+  check_perft(
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    &[6, 264, 9467],
+  );
+}
+
+#[test]
+fn perft_position_5() {
+  check_perft(
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    &[44, 1486, 62379],
+  );
+}
+
+#[test]
+fn perft_position_6() {
+  check_perft(
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    &[46, 2079, 89890],
+  );
+}
+
+// from the Chess960 perft results, rights are given as rook files
+#[test]
+fn perft_chess960() {
+  check_perft(
+    "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    &[21, 528, 12189, 326672],
+  );
+  check_perft(
+    "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+    &[21, 807, 18002, 667366],
+  );
+  check_perft(
+    "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+    &[20, 479, 10471, 273318],
+  );
+}
@@ -0,0 +1,71 @@
+use board_editor::{
+  annotation::{AnnotationColor, Annotations},
+  pgn::{generate_pgn, parse_pgn, PgnError, PgnTags},
+  position::Position,
+  san::{get_san, parse_san},
+};
+
+fn get_sans(pgn: &str) -> Vec<String> {
+  let game = parse_pgn(pgn).unwrap();
+  let positions = game.get_positions();
+  game
+    .moves
+    .iter()
+    .zip(positions.iter())
+    .map(|(mv, position)| get_san(position, mv))
+    .collect()
+}
+
+#[test]
+fn comments_nags_and_variations_are_skipped() {
+  let pgn = "[Event \"Test\"]\n[White \"A\"]\n\n\
+    1. e4 {best by test} e5 2. Nf3 $1 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6 ; a comment\n\
+    3.Bb5 a6!? 4. Ba4 Nf6 5. O-O 1-0";
+  let game = parse_pgn(pgn).unwrap();
+  assert_eq!(game.get_tag("White"), Some("A"));
+  assert_eq!(game.result, "1-0");
+  assert_eq!(
+    get_sans(pgn),
+    ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6", "O-O"]
+  );
+}
+
+#[test]
+fn games_start_from_the_fen_tag() {
+  let pgn = "[SetUp \"1\"]\n[FEN \"7k/P7/8/8/8/8/8/K7 w - - 0 1\"]\n\n1. a8Q+ Kh7 2. Qb7+ *";
+  assert_eq!(get_sans(pgn), ["a8=Q+", "Kh7", "Qb7+"]);
+}
+
+#[test]
+fn ambiguous_moves_need_disambiguation() {
+  let position = Position::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+  assert_eq!(parse_san(&position, "Rd1"), None);
+  let mv = parse_san(&position, "Rad1").unwrap();
+  assert_eq!(get_san(&position, &mv), "Rad1");
+}
+
+#[test]
+fn illegal_moves_are_reported() {
+  assert_eq!(
+    parse_pgn("1. e4 e5 2. Ke3"),
+    Err(PgnError::IllegalMove {
+      move_number: 2,
+      san: "Ke3".to_string()
+    })
+  );
+}
+
+#[test]
+fn annotations_are_exported_as_comments() {
+  let position = Position::starting_position();
+  let mut annotations = Annotations::new();
+  annotations.toggle_arrow((6, 4), (4, 4), AnnotationColor::Green);
+  annotations.toggle_arrow((7, 6), (5, 5), AnnotationColor::Red);
+  annotations.toggle_highlight((4, 3), AnnotationColor::Yellow);
+  // toggling twice removes the highlight
+  annotations.toggle_highlight((3, 3), AnnotationColor::Blue);
+  annotations.toggle_highlight((3, 3), AnnotationColor::Blue);
+  let pgn = generate_pgn(&position, &PgnTags::default(), &annotations);
+  assert!(pgn.ends_with("\n{[%csl Yd4][%cal Ge2e4,Rg1f3]} *\n"));
+  assert!(parse_pgn(pgn.as_str()).is_ok());
+}
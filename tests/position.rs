@@ -0,0 +1,112 @@
+use board_editor::{
+  fen::FenError,
+  position::{Piece, Position},
+};
+
+#[test]
+fn pieces_are_put_and_removed() {
+  let mut position = Position::new();
+  assert_eq!(position.get_piece((7, 4)), None);
+  position.put_piece((7, 4), 1, Piece::King);
+  position.put_piece((0, 4), 0, Piece::King);
+  assert_eq!(position.get_piece((7, 4)), Some((1, Piece::King)));
+  assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+  position.put_piece((7, 4), 0, Piece::Queen);
+  assert_eq!(position.get_piece((7, 4)), Some((0, Piece::Queen)));
+  position.remove_piece((7, 4));
+  assert_eq!(position.get_piece((7, 4)), None);
+  assert_eq!(position, {
+    let mut expected = Position::new();
+    expected.put_piece((0, 4), 0, Piece::King);
+    expected
+  });
+}
+
+#[test]
+fn fens_survive_a_round_trip() {
+  for fen in [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 12 40",
+    "8/8/8/4k3/8/8/8/4K3 w - - 0 1",
+  ] {
+    let position = Position::from_fen(fen).unwrap();
+    assert_eq!(position.to_fen(), fen);
+    assert_eq!(Position::from_fen(&position.to_fen()).unwrap(), position);
+  }
+  assert_eq!(
+    Position::starting_position().to_fen(),
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+  );
+}
+
+#[test]
+fn malformed_fields_are_reported() {
+  let error = |fen: &str| Position::from_fen(fen).unwrap_err();
+  assert_eq!(
+    error("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    FenError::InvalidRankLength(7)
+  );
+  assert_eq!(
+    error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1"),
+    FenError::InvalidRankLength(1)
+  );
+  assert_eq!(
+    error("rnbqkbnr/pppppppp/8/8/4X3/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    FenError::InvalidPiece('X')
+  );
+  assert_eq!(
+    error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+    FenError::InvalidSideToMove("x".to_string())
+  );
+  assert_eq!(
+    error("r3k2r/8/8/8/8/8/8/R3K2R w KQxq - 0 1"),
+    FenError::InvalidCastling("KQxq".to_string())
+  );
+  assert_eq!(
+    error("r3k2r/8/8/8/8/8/8/R3K2R w KKq - 0 1"),
+    FenError::InvalidCastling("KKq".to_string())
+  );
+  assert_eq!(
+    error("4k3/8/8/8/8/8/8/4K3 w - e5 0 1"),
+    FenError::InvalidEnPassant("e5".to_string())
+  );
+  assert_eq!(
+    error("4k3/8/8/8/8/8/8/4K3 w - j6 0 1"),
+    FenError::InvalidEnPassant("j6".to_string())
+  );
+  assert_eq!(
+    error("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+    FenError::InvalidHalfmoveClock("x".to_string())
+  );
+  assert_eq!(
+    error("4k3/8/8/8/8/8/8/4K3 w - - 0 one"),
+    FenError::InvalidFullmoveNumber("one".to_string())
+  );
+}
+
+#[test]
+fn automatic_castling_rights_follow_edits() {
+  let none = ((false, false), (false, false));
+  // kings and rooks off their squares never get rights
+  let mut position = Position::from_fen("4k3/8/8/8/8/8/8/3K3R w - - 0 1").unwrap();
+  position.update_auto_castling(none);
+  assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/3K3R w - - 0 1");
+  // the king reaching e1 makes white kingside castling possible
+  let before = position.get_possible_castling();
+  position.remove_piece((7, 3));
+  position.put_piece((7, 4), 1, Piece::King);
+  position.update_auto_castling(before);
+  assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+  // a right turned off by hand stays off while nothing changes
+  position.castle_white = (false, false);
+  position.update_auto_castling(position.get_possible_castling());
+  assert_eq!(position.castle_white, (false, false));
+  // and a rook leaving its square takes the right away
+  position.castle_white = (true, false);
+  let before = position.get_possible_castling();
+  position.remove_piece((7, 7));
+  position.put_piece((6, 7), 1, Piece::Rook);
+  position.update_auto_castling(before);
+  assert_eq!(position.castle_white, (false, false));
+}
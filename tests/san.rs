@@ -0,0 +1,43 @@
+use board_editor::{
+  position::Position,
+  san::{get_san, parse_move_line},
+};
+
+fn get_sans(position: &Position, line: &str) -> (Vec<String>, Option<String>) {
+  let (moves, error) = parse_move_line(position, line);
+  let mut position = position.clone();
+  let sans = moves
+    .iter()
+    .map(|mv| {
+      let san = get_san(&position, mv);
+      position.make_move(mv);
+      san
+    })
+    .collect();
+  (sans, error)
+}
+
+#[test]
+fn san_and_coordinates_can_be_mixed() {
+  let start = Position::starting_position();
+  let (sans, error) = get_sans(&start, "1. e4 e7e5 2.Nf3 b8-c6 Bb5 a7a6");
+  assert_eq!(sans, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+  assert_eq!(error, None);
+}
+
+#[test]
+fn the_line_stops_at_an_illegal_move() {
+  let start = Position::starting_position();
+  let (sans, error) = get_sans(&start, "1. e4 e5 2. Ke3 Nc6 3. Bb5");
+  assert_eq!(sans, ["e4", "e5"]);
+  assert_eq!(error.as_deref(), Some("Ke3 is not a legal move"));
+}
+
+#[test]
+fn the_error_names_the_token_as_typed() {
+  let start = Position::starting_position();
+  let (moves, error) = parse_move_line(&start, "1.e4 2.e2e4");
+  assert_eq!(moves.len(), 1);
+  assert_eq!(error.as_deref(), Some("2.e2e4 is not a legal move"));
+  assert_eq!(parse_move_line(&start, "  "), (Vec::new(), None));
+}
@@ -0,0 +1,40 @@
+use board_editor::{position::Position, status::Status};
+
+fn get_status(fen: &str) -> Status {
+  Position::from_fen(fen).unwrap().get_status()
+}
+
+fn is_insufficient(fen: &str) -> bool {
+  Position::from_fen(fen).unwrap().is_insufficient_material()
+}
+
+#[test]
+fn check_mate_and_stalemate_are_told_apart() {
+  assert_eq!(
+    get_status("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    Status::Normal
+  );
+  assert_eq!(get_status("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1"), Status::Check);
+  // fool's mate
+  assert_eq!(
+    get_status("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"),
+    Status::Checkmate
+  );
+  assert_eq!(
+    get_status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"),
+    Status::Stalemate
+  );
+}
+
+#[test]
+fn insufficient_material_is_detected() {
+  assert!(is_insufficient("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+  assert!(is_insufficient("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+  assert!(is_insufficient("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1"));
+  // bishops on c1 and f8 both stand on dark squares
+  assert!(is_insufficient("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+  assert!(!is_insufficient("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+  assert!(!is_insufficient("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1"));
+  assert!(!is_insufficient("4k3/8/8/8/8/8/8/1N1BK3 w - - 0 1"));
+  assert!(!is_insufficient("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+}
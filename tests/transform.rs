@@ -0,0 +1,71 @@
+use board_editor::{
+  position::Position,
+  transform::{mirror_files, shift_pieces, swap_colours},
+};
+
+fn transform_fen(fen: &str, transform: fn(&Position) -> Position) -> String {
+  transform(&Position::from_fen(fen).unwrap()).to_fen()
+}
+
+#[test]
+fn mirroring_swaps_the_castling_sides() {
+  // classical castling needs the king on the e file
+  assert_eq!(
+    transform_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", mirror_files),
+    "r2k3r/8/8/8/8/8/8/R2K3R w - - 0 1"
+  );
+  // with the king on e an inner rook needs its file
+  let mut position = Position::from_fen("1r2k2r/8/8/8/8/8/8/R3K1R1 w Gk - 0 1").unwrap();
+  position.set_chess960(true);
+  let mirrored = mirror_files(&position);
+  assert_eq!(mirrored.to_fen(), "r2k2r1/8/8/8/8/8/8/1R1K3R w Qq - 0 1");
+  assert_eq!(mirrored.castling_files, [(6, 0), (7, 1)]);
+  assert_eq!(
+    transform_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", mirror_files),
+    "3k4/8/8/3Pp3/8/8/8/3K4 w - e6 0 2"
+  );
+}
+
+#[test]
+fn swapping_colours_flips_the_ranks() {
+  assert_eq!(
+    transform_fen(
+      "rnbqkbnr/ppppp1pp/8/8/4Pp2/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3",
+      swap_colours
+    ),
+    "rnbqkbnr/pppp1ppp/8/4pP2/8/8/PPPPP1PP/RNBQKBNR w KQkq e6 0 3"
+  );
+  let position = Position::from_fen("r3k3/8/8/8/8/8/8/4K2R b Kq - 0 1").unwrap();
+  assert_eq!(swap_colours(&swap_colours(&position)), position);
+}
+
+#[test]
+fn shifting_keeps_pieces_on_the_board() {
+  let position = Position::from_fen("8/8/8/3k4/8/3K4/8/7R w - - 0 1").unwrap();
+  let shifted = shift_pieces(&position, -1, -1).unwrap();
+  assert_eq!(shifted.to_fen(), "8/8/2k5/8/2K5/8/6R1/8 w - - 0 1");
+  assert_eq!(shift_pieces(&position, 0, 1), None);
+  assert_eq!(shift_pieces(&position, 1, 0), None);
+  // a pushed pawn stops being one once it moved off its rank
+  let position = Position::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+  assert_eq!(
+    shift_pieces(&position, 0, -1).unwrap().to_fen(),
+    "3k4/8/8/2pP4/8/8/8/3K4 w - c6 0 2"
+  );
+  let position = Position::from_fen("8/4k3/8/3pP3/8/8/4K3/8 w - d6 0 2").unwrap();
+  assert_eq!(
+    shift_pieces(&position, 1, 0).unwrap().to_fen(),
+    "8/8/4k3/8/3pP3/8/8/4K3 w - - 0 2"
+  );
+}
+
+#[test]
+fn pawns_are_not_shifted_onto_a_back_rank() {
+  let position = Position::from_fen("8/4P3/8/3k4/8/3K4/3p4/8 w - - 0 1").unwrap();
+  assert_eq!(shift_pieces(&position, -1, 0), None);
+  assert_eq!(shift_pieces(&position, 1, 0), None);
+  assert_eq!(
+    shift_pieces(&position, 0, 1).unwrap().to_fen(),
+    "8/5P2/8/4k3/8/4K3/4p3/8 w - - 0 1"
+  );
+}
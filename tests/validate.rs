@@ -0,0 +1,100 @@
+use board_editor::{position::Position, validate::Problem};
+
+fn validate(fen: &str) -> Vec<Problem> {
+  Position::from_fen(fen).unwrap().validate()
+}
+
+#[test]
+fn the_starting_position_is_legal() {
+  assert_eq!(Position::starting_position().validate(), Vec::new());
+}
+
+#[test]
+fn each_side_needs_one_king() {
+  assert_eq!(
+    validate("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+    vec![Problem::KingCount {
+      player: 0,
+      count: 0
+    }]
+  );
+  assert_eq!(
+    validate("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+    vec![Problem::KingCount {
+      player: 1,
+      count: 2
+    }]
+  );
+}
+
+#[test]
+fn pawns_cannot_stand_on_back_ranks() {
+  assert_eq!(
+    validate("P3k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+    vec![
+      Problem::PawnOnBackRank { square: (0, 0) },
+      Problem::PawnOnBackRank { square: (7, 7) },
+    ]
+  );
+}
+
+#[test]
+fn material_is_limited() {
+  let problems = validate("4k3/8/8/QQQQQQQQ/QQQQQQQQ/8/8/4K3 w - - 0 1");
+  assert!(problems.contains(&Problem::TooManyPieces {
+    player: 1,
+    count: 17
+  }));
+  assert!(
+    validate("4k3/8/PPPPPPPP/P7/8/8/8/4K3 w - - 0 1").contains(&Problem::TooManyPawns {
+      player: 1,
+      count: 9
+    })
+  );
+  // seven pawns leave room for one promoted piece, not two
+  assert_eq!(
+    validate("4k3/8/8/8/8/QQQ5/PPPPPPP1/4K3 w - - 0 1"),
+    vec![Problem::TooManyPromotions {
+      player: 1,
+      count: 1
+    }]
+  );
+}
+
+#[test]
+fn the_side_not_to_move_cannot_be_in_check() {
+  assert_eq!(
+    validate("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"),
+    vec![Problem::OpponentInCheck]
+  );
+  assert_eq!(validate("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1"), Vec::new());
+}
+
+#[test]
+fn double_checks_need_a_line_piece() {
+  assert!(matches!(
+    &validate("4k3/8/3N1N2/8/8/8/8/4K3 b - - 0 1")[..],
+    [Problem::ImpossibleCheck { checkers }] if checkers.len() == 2
+  ));
+  assert_eq!(validate("4k3/8/3N4/8/8/8/8/4RK2 b - - 0 1"), Vec::new());
+}
+
+#[test]
+fn castling_rights_need_the_king_and_rook_at_home() {
+  let mut position = Position::from_fen("r3k3/8/8/8/8/8/8/4K2R w - - 0 1").unwrap();
+  position.castle_white = (true, true);
+  position.castle_black = (true, true);
+  assert_eq!(
+    position.validate(),
+    vec![
+      Problem::CastlingRights {
+        player: 1,
+        kingside: false
+      },
+      Problem::CastlingRights {
+        player: 0,
+        kingside: true
+      },
+    ]
+  );
+}